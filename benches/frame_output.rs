@@ -0,0 +1,80 @@
+//! Gibt einen vollständigen Frame einmal über den Zeilenpuffer von `Stdout` und einmal
+//! über einen `BufWriter` in Frame-Größe aus; dass der Puffer die Schreibaufrufe senkt,
+//! prüft ein Test im Binary.
+//!
+//! `cargo bench --bench frame_output`
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use matrix::{CrosstermRenderer, Grid, RainConfig, RainState, RenderOptions, Renderer};
+use std::io::{BufWriter, LineWriter, Write};
+use std::time::Duration;
+
+/// Großes Terminal, in dem jede Zelle neu gezeichnet wird
+const WIDTH: u16 = 200;
+const HEIGHT: u16 = 60;
+/// Wie `BYTES_PER_CELL` im Binary
+const BYTES_PER_CELL: usize = 48;
+
+/// Senke, die nur die Schreibaufrufe (≈ Syscalls) zählt.
+#[derive(Default)]
+struct CountingSink {
+    writes: usize,
+}
+
+impl Write for CountingSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writes += 1;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn frame() -> Grid {
+    let config = RainConfig {
+        seed: Some(7),
+        ..RainConfig::default()
+    };
+    let mut state = RainState::with_config(config, WIDTH, HEIGHT);
+    for _ in 0..120 {
+        state.step(Duration::from_millis(16));
+    }
+    state.grid().clone()
+}
+
+/// Jede Zelle über den Terminal-Renderer des Binarys, in Farbe
+fn draw(out: &mut impl Write, grid: &Grid) -> std::io::Result<()> {
+    let options = RenderOptions {
+        color: true,
+        ..RenderOptions::default()
+    };
+    CrosstermRenderer::new(out, options).draw_grid(grid, None)?;
+    Ok(())
+}
+
+/// Schreibaufrufe für einen Frame; ohne `capacity` über einen `LineWriter` wie `Stdout`.
+fn writes_per_frame(grid: &Grid, capacity: Option<usize>) -> usize {
+    let mut sink = CountingSink::default();
+    match capacity {
+        Some(capacity) => draw(&mut BufWriter::with_capacity(capacity, &mut sink), grid),
+        None => draw(&mut LineWriter::new(&mut sink), grid),
+    }
+    .unwrap();
+    sink.writes
+}
+
+fn output(c: &mut Criterion) {
+    let grid = frame();
+    let capacity = WIDTH as usize * HEIGHT as usize * BYTES_PER_CELL;
+    c.bench_function("frame_output/line_writer", |b| {
+        b.iter(|| writes_per_frame(&grid, None))
+    });
+    c.bench_function("frame_output/buf_writer", |b| {
+        b.iter(|| writes_per_frame(&grid, Some(capacity)))
+    });
+}
+
+criterion_group!(benches, output);
+criterion_main!(benches);
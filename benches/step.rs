@@ -0,0 +1,43 @@
+//! Misst einen einzelnen Simulationsschritt (`RainState::step`) ohne Terminal bei
+//! typischen und sehr großen Rastergrößen.
+//!
+//! `cargo bench --bench step`
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use matrix::{RainConfig, RainState};
+use std::hint::black_box;
+use std::time::Duration;
+
+/// Standard-Terminal, großes Terminal, sehr großes Terminal
+const SIZES: [(u16, u16); 3] = [(80, 24), (200, 60), (400, 120)];
+/// Frame-Dauer bei 60 FPS
+const DT: Duration = Duration::from_millis(16);
+
+/// Simulation mit festem Seed, vorgewärmt, bis der Regen den Bildschirm füllt
+fn warm_state(width: u16, height: u16) -> RainState {
+    let config = RainConfig {
+        seed: Some(7),
+        ..RainConfig::default()
+    };
+    let mut state = RainState::with_config(config, width, height);
+    for _ in 0..120 {
+        state.step(DT);
+    }
+    state
+}
+
+fn step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("step");
+    for (width, height) in SIZES {
+        let mut state = warm_state(width, height);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{width}x{height}")),
+            &DT,
+            |b, &dt| b.iter(|| state.step(black_box(dt)).width()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, step);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+//! Vergleicht die Spurfarben pro Zelle mit der vorberechneten Tabelle.
+//!
+//! `cargo bench --bench trail_colors`
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use matrix::{Color, ColorSet, ColorSetName};
+use std::hint::black_box;
+
+/// Spalten eines breiten Terminals mal Standard-Spurlänge
+const COLUMNS: usize = 200;
+const TRAIL_LEN: usize = 10;
+
+fn per_cell(c: &mut Criterion) {
+    let colorset = ColorSet::from_name(ColorSetName::C2077);
+    c.bench_function("trail_colors/per_cell", |b| {
+        b.iter(|| {
+            let mut last = Color::Reset;
+            for _ in 0..COLUMNS {
+                for offset in 0..TRAIL_LEN {
+                    let t = offset as f32 / (TRAIL_LEN - 1) as f32;
+                    last = colorset.gradient_color(black_box(1.0 - t));
+                }
+            }
+            last
+        })
+    });
+}
+
+fn lookup_table(c: &mut Criterion) {
+    let colorset = ColorSet::from_name(ColorSetName::C2077);
+    let table: Vec<Color> = colorset.sample_n(TRAIL_LEN).into_iter().rev().collect();
+    c.bench_function("trail_colors/table", |b| {
+        b.iter(|| {
+            let mut last = Color::Reset;
+            for _ in 0..COLUMNS {
+                for offset in 0..TRAIL_LEN {
+                    last = table[black_box(offset)];
+                }
+            }
+            last
+        })
+    });
+}
+
+criterion_group!(benches, per_cell, lookup_table);
+criterion_main!(benches);
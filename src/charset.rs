@@ -0,0 +1,89 @@
+use clap::ValueEnum;
+
+/// Vordefinierte Zeichensätze für den Regen.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+pub enum Charset {
+    /// Lateinische Buchstaben und Ziffern
+    #[default]
+    Ascii,
+    /// Halbbreite Katakana (U+FF66–U+FF9D) wie im Film
+    Katakana,
+    /// Nur `0` und `1`
+    Binary,
+}
+
+impl Charset {
+    /// Zeichen des Satzes in fester Reihenfolge.
+    ///
+    /// ```
+    /// use matrix::Charset;
+    ///
+    /// let katakana = Charset::Katakana.chars();
+    /// assert_eq!(katakana.len(), 56);
+    /// assert_eq!(katakana.first(), Some(&'ｦ'));
+    /// assert_eq!(Charset::Binary.chars(), ['0', '1']);
+    /// ```
+    pub fn chars(self) -> Vec<char> {
+        match self {
+            Charset::Ascii => "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"
+                .chars()
+                .collect(),
+            Charset::Katakana => ('\u{FF66}'..='\u{FF9D}').collect(),
+            Charset::Binary => vec!['0', '1'],
+        }
+    }
+
+    /// Zeichen des Satzes ohne die in `filter` abgewählten Klassen; kann leer sein.
+    pub fn chars_filtered(self, filter: CharFilter) -> Vec<char> {
+        let mut chars = self.chars();
+        chars.retain(|&ch| filter.keeps(ch));
+        chars
+    }
+}
+
+/// Zeichenklassen, die aus einem vordefinierten Zeichensatz herausfallen.
+///
+/// ```
+/// use matrix::{CharFilter, Charset};
+///
+/// let filter = CharFilter {
+///     uppercase: false,
+///     lowercase: false,
+///     ..CharFilter::default()
+/// };
+/// let chars = Charset::Ascii.chars_filtered(filter);
+/// assert_eq!(chars.len(), 10);
+/// assert!(chars.iter().all(char::is_ascii_digit));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CharFilter {
+    pub uppercase: bool,
+    pub lowercase: bool,
+    pub digits: bool,
+}
+
+impl Default for CharFilter {
+    fn default() -> Self {
+        Self {
+            uppercase: true,
+            lowercase: true,
+            digits: true,
+        }
+    }
+}
+
+impl CharFilter {
+    /// `false` für Großbuchstaben, Kleinbuchstaben oder Ziffern, deren Klasse abgewählt ist;
+    /// alle anderen Zeichen bleiben.
+    pub fn keeps(self, ch: char) -> bool {
+        if ch.is_uppercase() {
+            self.uppercase
+        } else if ch.is_lowercase() {
+            self.lowercase
+        } else if ch.is_ascii_digit() {
+            self.digits
+        } else {
+            true
+        }
+    }
+}
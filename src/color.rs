@@ -0,0 +1,671 @@
+use clap::ValueEnum;
+use crossterm::style::Color;
+use std::cmp::min;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Eingebaute Farbsets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ColorSetName {
+    Determination,
+    City,
+    #[value(name = "2077")]
+    C2077,
+    Thermography,
+}
+
+impl ColorSetName {
+    /// Name wie auf der Kommandozeile (`--colorset`).
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorSetName::Determination => "determination",
+            ColorSetName::City => "city",
+            ColorSetName::C2077 => "2077",
+            ColorSetName::Thermography => "thermography",
+        }
+    }
+}
+
+/// Alle eingebauten Farbsets mit ihrem Namen, in der Reihenfolge von [`ColorSetName`].
+pub fn builtin_sets() -> &'static [(&'static str, ColorSet)] {
+    static SETS: OnceLock<Vec<(&'static str, ColorSet)>> = OnceLock::new();
+    SETS.get_or_init(|| {
+        ColorSetName::value_variants()
+            .iter()
+            .map(|name| (name.name(), ColorSet::from_name(*name)))
+            .collect()
+    })
+}
+
+/// Farbraum, in dem zwischen zwei Farben interpoliert wird.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BlendMode {
+    /// Direkt in sRGB (Standard, mittlere Töne wirken oft trüb)
+    #[default]
+    Srgb,
+    /// In linearem Licht
+    Linear,
+    /// Im wahrnehmungsgleichmäßigen OKLab-Raum
+    Oklab,
+}
+
+/// Farbtiefe der Terminalausgabe.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorDepth {
+    /// 24-Bit-RGB
+    #[default]
+    Truecolor,
+    /// xterm-256-Palette
+    #[value(name = "256")]
+    Ansi256,
+    /// Die 16 Standardfarben
+    #[value(name = "16")]
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Schätzt die Farbtiefe aus `COLORTERM` und `TERM`; unbekannte Terminals
+    /// bekommen Truecolor.
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::Truecolor;
+        }
+        let term = std::env::var("TERM").unwrap_or_default();
+        if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else if term == "linux" || term == "dumb" || term == "ansi" || term.starts_with("vt") {
+            ColorDepth::Ansi16
+        } else {
+            ColorDepth::Truecolor
+        }
+    }
+
+    /// Bildet eine Farbe auf die nächstgelegene Farbe dieser Tiefe ab.
+    pub fn quantize(self, color: Color) -> Color {
+        if self == ColorDepth::Truecolor || color == Color::Reset {
+            return color;
+        }
+        let (r, g, b) = color_to_rgb(color);
+        match self {
+            ColorDepth::Truecolor => color,
+            ColorDepth::Ansi256 => Color::AnsiValue(quantize_256(r, g, b)),
+            ColorDepth::Ansi16 => Color::AnsiValue(quantize_16(r, g, b)),
+        }
+    }
+}
+
+/// Abstufungen des 6x6x6-Farbwürfels der xterm-256-Palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Standardwerte der 16 ANSI-Farben (xterm)
+const ANSI_16: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// Index (16–255) der nächstgelegenen Farbe aus Farbwürfel oder Graustufen.
+pub fn quantize_256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |c: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - c as i32).abs())
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    let cube_index = (16 + 36 * ri + 6 * gi + bi) as u8;
+
+    // Graustufen 232–255 decken 8..=238 in Zehnerschritten ab
+    let avg = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = ((avg.saturating_sub(8) + 5) / 10).min(23) as u8;
+    let gray = 8 + 10 * gray_step;
+    let rgb = (r, g, b);
+    if distance(rgb, (gray, gray, gray)) < distance(rgb, cube) {
+        232 + gray_step
+    } else {
+        cube_index
+    }
+}
+
+/// Index (0–15) der nächstgelegenen ANSI-Standardfarbe.
+pub fn quantize_16(r: u8, g: u8, b: u8) -> u8 {
+    (0..ANSI_16.len())
+        .min_by_key(|&i| distance((r, g, b), ANSI_16[i]))
+        .unwrap_or(0) as u8
+}
+
+/// Farbverlauf, über den die Spuren eingefärbt werden.
+#[derive(Clone, Debug)]
+pub struct ColorSet {
+    colors: Vec<Color>,
+    /// Deckkraft je Farbe (255 = deckend), wird gegen `background` verrechnet
+    alphas: Vec<u8>,
+    blend: BlendMode,
+    /// Hintergrund, gegen den Farben mit Alpha verrechnet werden
+    background: Color,
+    /// Nachbearbeitung jeder Verlaufsfarbe, siehe [`ColorSet::with_brightness`]
+    brightness: f32,
+    contrast: f32,
+}
+
+impl ColorSet {
+    /// Baut eines der eingebauten Farbsets.
+    pub fn from_name(name: ColorSetName) -> Self {
+        match name {
+            ColorSetName::Determination => Self::from_hex(&["#39c4b6", "#fee801", "#6300ff"]),
+            ColorSetName::City => Self::from_hex(&["#ff0677", "#0051ff", "#8900ff"]),
+            ColorSetName::C2077 => Self::from_hex(&["#c5003c", "#880425", "#f3e600", "#55ead4"]),
+            ColorSetName::Thermography => {
+                Self::from_hex(&["#ff004a", "#ffcc3d", "#ff5631", "#ad00ff"])
+            }
+        }
+    }
+
+    /// Baut ein Farbset aus Hex-Strings; ungültige Einträge werden übersprungen.
+    pub fn from_hex(hexes: &[&str]) -> Self {
+        Self::from_rgba(hexes.iter().filter_map(|h| hex_to_rgba(h).ok()).collect())
+    }
+
+    /// Wie [`ColorSet::from_hex`], bricht aber beim ersten ungültigen Eintrag ab.
+    pub fn try_from_hex(hexes: &[&str]) -> Result<Self, ParseColorError> {
+        let parsed = hexes
+            .iter()
+            .map(|h| hex_to_rgba(h))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_rgba(parsed))
+    }
+
+    /// Farbset aus Farben mit Deckkraft; leer fällt es auf Grün zurück.
+    pub(crate) fn from_rgba(parsed: Vec<(Color, u8)>) -> Self {
+        let (mut colors, mut alphas): (Vec<Color>, Vec<u8>) = parsed.into_iter().unzip();
+        if colors.is_empty() {
+            colors.push(Color::Green);
+            alphas.push(u8::MAX);
+        }
+        Self {
+            colors,
+            alphas,
+            blend: BlendMode::Srgb,
+            background: Color::Black,
+            brightness: 0.0,
+            contrast: 1.0,
+        }
+    }
+
+    /// Gleicher Farbraum, aber mit den deckenden Farben `colors` (mindestens eine).
+    pub(crate) fn with_colors(&self, colors: Vec<Color>) -> Self {
+        let colors = if colors.is_empty() {
+            vec![Color::Green]
+        } else {
+            colors
+        };
+        Self {
+            alphas: vec![u8::MAX; colors.len()],
+            colors,
+            blend: self.blend,
+            background: self.background,
+            brightness: self.brightness,
+            contrast: self.contrast,
+        }
+    }
+
+    /// Legt den Hintergrund fest, gegen den teiltransparente Farben gemischt werden
+    /// (Standard: Schwarz).
+    pub fn with_background(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Hintergrund, gegen den teiltransparente Farben gemischt werden.
+    pub fn background(&self) -> Color {
+        self.background
+    }
+
+    /// Legt den Farbraum für die Interpolation fest.
+    pub fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// Verschiebt jede Verlaufsfarbe um `brightness` (-1.0–1.0) Richtung Weiß bzw.
+    /// Schwarz. Je RGB-Kanal `c` (0.0–1.0) ergibt sich zusammen mit
+    /// [`ColorSet::with_contrast`] `(c - 0.5) * contrast + 0.5 + brightness`, auf
+    /// 0.0–1.0 begrenzt: +1.0 ergibt also Weiß, -1.0 Schwarz.
+    pub fn with_brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness.clamp(-1.0, 1.0);
+        self
+    }
+
+    /// Streckt (> 1.0) oder staucht (< 1.0) jede Verlaufsfarbe um das mittlere Grau;
+    /// 0.0 ergibt einheitliches Grau (verschoben um `brightness`), 1.0 lässt sie
+    /// unverändert. Bereich 0.0–2.0.
+    pub fn with_contrast(mut self, contrast: f32) -> Self {
+        self.contrast = contrast.clamp(0.0, 2.0);
+        self
+    }
+
+    /// Wendet Helligkeit und Kontrast auf eine Farbe an; neutral bleibt sie unverändert.
+    fn adjust(&self, color: Color) -> Color {
+        if self.brightness == 0.0 && self.contrast == 1.0 {
+            return color;
+        }
+        let channel = |c: u8| {
+            let c = (c as f32 / 255.0 - 0.5) * self.contrast + 0.5 + self.brightness;
+            (c.clamp(0.0, 1.0) * 255.0).round() as u8
+        };
+        let (r, g, b) = color_to_rgb(color);
+        Color::Rgb {
+            r: channel(r),
+            g: channel(g),
+            b: channel(b),
+        }
+    }
+
+    /// Farbe an Position `t` des Verlaufs (0.0 = erste, 1.0 = letzte Farbe).
+    pub fn gradient_color(&self, t: f32) -> Color {
+        // t in [0,1], map über Palette
+        if self.colors.len() == 1 {
+            return self.adjust(over(self.background, self.colors[0], self.alphas[0] as f32));
+        }
+        let n = self.colors.len();
+        let scaled = t.clamp(0.0, 1.0) * (n as f32 - 1.0);
+        let i = scaled.floor() as usize;
+        let j = min(i + 1, n - 1);
+        let local_t = scaled - i as f32;
+
+        let alpha =
+            self.alphas[i] as f32 + (self.alphas[j] as f32 - self.alphas[i] as f32) * local_t;
+        let color = blend_color_in(self.colors[i], self.colors[j], local_t, self.blend);
+        self.adjust(over(self.background, color, alpha))
+    }
+
+    /// Farbe an Position `t` (0.0–1.0) des Verlaufs; gleichbedeutend mit [`Self::gradient_color`].
+    pub fn color_at_ratio(&self, t: f32) -> Color {
+        self.gradient_color(t)
+    }
+
+    /// `n` gleichmäßig verteilte Farben des Verlaufs, von der ersten bis zur letzten;
+    /// als Tabelle vorberechnet spart das `gradient_color` pro Zelle.
+    pub fn sample_n(&self, n: usize) -> Vec<Color> {
+        let last = n.saturating_sub(1).max(1) as f32;
+        (0..n)
+            .map(|i| self.gradient_color(i as f32 / last))
+            .collect()
+    }
+}
+
+/// Legt eine Farbe mit Alpha (0-255) über den Hintergrund.
+fn over(background: Color, color: Color, alpha: f32) -> Color {
+    if alpha >= u8::MAX as f32 {
+        return color;
+    }
+    blend_color(background, color, alpha / u8::MAX as f32)
+}
+
+/// Wandelt HSV in eine RGB-Farbe; `h` in Grad (beliebig, wird auf 0–360 gefaltet),
+/// `s` und `v` von 0.0 bis 1.0.
+pub fn hsv_to_color(h: f32, s: f32, v: f32) -> Color {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u8 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    let to_u8 = |f: f32| ((f + m) * 255.0).round() as u8;
+    Color::Rgb {
+        r: to_u8(r),
+        g: to_u8(g),
+        b: to_u8(b),
+    }
+}
+
+/// Dreht den Farbton um `degrees` bei gleicher Sättigung und Helligkeit.
+pub(crate) fn rotate_hue(color: Color, degrees: f32) -> Color {
+    let (r, g, b) = color_to_rgb(color);
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    if delta == 0.0 {
+        return color;
+    }
+    let hue = if max == r {
+        60.0 * ((g - b) / delta)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    hsv_to_color(hue + degrees, delta / max, max)
+}
+
+/// Fehler beim Parsen einer Hex-Farbe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseColorError {
+    /// Zeichen, das keine Hexadezimalziffer ist
+    InvalidDigit(char),
+    /// Ziffernzahl (ohne `#`) ist nicht 3, 4, 6 oder 8
+    InvalidLength(usize),
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseColorError::InvalidDigit(ch) => write!(f, "`{ch}` ist keine Hexadezimalziffer"),
+            ParseColorError::InvalidLength(len) => {
+                write!(f, "{len} Ziffern statt 3, 4, 6 oder 8")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+/// Parst einen Hex-String (`#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`) zu einer Farbe.
+pub fn hex_to_color(hex: &str) -> Result<Color, ParseColorError> {
+    hex_to_rgba(hex).map(|(c, _)| c)
+}
+
+/// Parst `#rgb`, `#rgba`, `#rrggbb` und `#rrggbbaa`; Alpha ist 255, wenn nicht angegeben.
+pub(crate) fn hex_to_rgba(hex: &str) -> Result<(Color, u8), ParseColorError> {
+    let h = hex.trim().trim_start_matches('#');
+    if let Some(ch) = h.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(ParseColorError::InvalidDigit(ch));
+    }
+    // Ab hier nur ASCII-Hexziffern, Byte-Indizes sind also Zeichen;
+    // Kurzformen verdoppeln jedes Nibble (f -> ff)
+    let digit = |i: usize| (h.as_bytes()[i] as char).to_digit(16).unwrap_or(0) as u8;
+    let nibble = |i: usize| digit(i) * 17;
+    let byte = |i: usize| digit(i) << 4 | digit(i + 1);
+    let (r, g, b, a) = match h.len() {
+        3 => (nibble(0), nibble(1), nibble(2), u8::MAX),
+        4 => (nibble(0), nibble(1), nibble(2), nibble(3)),
+        6 => (byte(0), byte(2), byte(4), u8::MAX),
+        8 => (byte(0), byte(2), byte(4), byte(6)),
+        len => return Err(ParseColorError::InvalidLength(len)),
+    };
+    Ok((Color::Rgb { r, g, b }, a))
+}
+
+pub(crate) fn blend_color(a: Color, b: Color, t: f32) -> Color {
+    let (ar, ag, ab) = color_to_rgb(a);
+    let (br, bg, bb) = color_to_rgb(b);
+    let t = t.clamp(0.0, 1.0);
+    let r = (ar as f32 + (br as f32 - ar as f32) * t) as u8;
+    let g = (ag as f32 + (bg as f32 - ag as f32) * t) as u8;
+    let b = (ab as f32 + (bb as f32 - ab as f32) * t) as u8;
+    Color::Rgb { r, g, b }
+}
+
+/// Interpoliert zwischen zwei Farben im gewählten Farbraum.
+pub(crate) fn blend_color_in(a: Color, b: Color, t: f32, mode: BlendMode) -> Color {
+    let to_space = match mode {
+        BlendMode::Srgb => return blend_color(a, b, t),
+        BlendMode::Linear => rgb_to_linear,
+        BlendMode::Oklab => rgb_to_oklab,
+    };
+    let (ar, ag, ab) = color_to_rgb(a);
+    let (br, bg, bb) = color_to_rgb(b);
+    let (pa, pb) = (to_space(ar, ag, ab), to_space(br, bg, bb));
+    let t = t.clamp(0.0, 1.0);
+    let mixed = [
+        pa[0] + (pb[0] - pa[0]) * t,
+        pa[1] + (pb[1] - pa[1]) * t,
+        pa[2] + (pb[2] - pa[2]) * t,
+    ];
+    if mode == BlendMode::Linear {
+        linear_to_color(mixed)
+    } else {
+        oklab_to_color(mixed)
+    }
+}
+
+fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let s = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0).round() as u8
+}
+
+fn rgb_to_linear(r: u8, g: u8, b: u8) -> [f32; 3] {
+    [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b)]
+}
+
+fn linear_to_color([r, g, b]: [f32; 3]) -> Color {
+    Color::Rgb {
+        r: linear_to_srgb(r),
+        g: linear_to_srgb(g),
+        b: linear_to_srgb(b),
+    }
+}
+
+// Umrechnung nach Björn Ottosson, https://bottosson.github.io/posts/oklab/
+fn rgb_to_oklab(r: u8, g: u8, b: u8) -> [f32; 3] {
+    let [r, g, b] = rgb_to_linear(r, g, b);
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+fn oklab_to_color([l, a, b]: [f32; 3]) -> Color {
+    let l_ = (l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+    let m_ = (l - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+    let s_ = (l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+    linear_to_color([
+        4.076_741_7 * l_ - 3.307_711_6 * m_ + 0.230_969_94 * s_,
+        -1.268_438 * l_ + 2.609_757_4 * m_ - 0.341_319_38 * s_,
+        -0.004_196_086_3 * l_ - 0.703_418_6 * m_ + 1.707_614_7 * s_,
+    ])
+}
+
+pub(crate) fn color_to_rgb(c: Color) -> (u8, u8, u8) {
+    match c {
+        Color::Rgb { r, g, b } => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::DarkGrey => (80, 80, 80),
+        Color::Grey => (128, 128, 128),
+        Color::White => (255, 255, 255),
+        Color::Red => (255, 0, 0),
+        Color::DarkRed => (128, 0, 0),
+        Color::Green => (0, 255, 0),
+        Color::DarkGreen => (0, 128, 0),
+        Color::Blue => (0, 0, 255),
+        Color::DarkBlue => (0, 0, 128),
+        Color::Yellow => (255, 255, 0),
+        Color::DarkYellow => (128, 128, 0),
+        Color::Magenta => (255, 0, 255),
+        Color::DarkMagenta => (128, 0, 128),
+        Color::Cyan => (0, 255, 255),
+        Color::DarkCyan => (0, 128, 128),
+        Color::AnsiValue(i) if (i as usize) < ANSI_16.len() => ANSI_16[i as usize],
+        _ => (0, 255, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color::Rgb { r, g, b }
+    }
+
+    #[test]
+    fn hex_short_forms_expand_nibbles() {
+        assert_eq!(hex_to_rgba("#f80"), Ok((rgb(0xff, 0x88, 0x00), 255)));
+        assert_eq!(hex_to_rgba("#0f0a"), Ok((rgb(0x00, 0xff, 0x00), 0xaa)));
+    }
+
+    #[test]
+    fn hex_long_forms_keep_bytes() {
+        assert_eq!(hex_to_rgba("#12ab9f"), Ok((rgb(0x12, 0xab, 0x9f), 255)));
+        assert_eq!(hex_to_rgba("#12ab9f40"), Ok((rgb(0x12, 0xab, 0x9f), 0x40)));
+        // Ohne `#` und mit Leerraum drumherum
+        assert_eq!(hex_to_rgba(" 12AB9F "), Ok((rgb(0x12, 0xab, 0x9f), 255)));
+    }
+
+    #[test]
+    fn hex_rejects_malformed_input() {
+        assert_eq!(hex_to_rgba("#xyz"), Err(ParseColorError::InvalidDigit('x')));
+        assert_eq!(
+            hex_to_rgba("#12345"),
+            Err(ParseColorError::InvalidLength(5))
+        );
+        assert_eq!(hex_to_rgba("#"), Err(ParseColorError::InvalidLength(0)));
+    }
+
+    #[test]
+    fn blend_modes_differ_at_midpoint() {
+        let mid = |mode| blend_color_in(rgb(255, 0, 0), rgb(0, 255, 0), 0.5, mode);
+        assert_eq!(mid(BlendMode::Srgb), rgb(127, 127, 0));
+        assert_eq!(mid(BlendMode::Linear), rgb(188, 188, 0));
+        // OKLab hält die Helligkeit und verschiebt den Farbton Richtung Orange
+        let Color::Rgb { r, g, b } = mid(BlendMode::Oklab) else {
+            panic!()
+        };
+        assert!(r > g && g > 127 && b == 0, "{r} {g} {b}");
+    }
+
+    #[test]
+    fn quantize_256_maps_known_colors() {
+        assert_eq!(quantize_256(0, 0, 0), 16);
+        assert_eq!(quantize_256(255, 0, 0), 196);
+        assert_eq!(quantize_256(0, 255, 0), 46);
+        assert_eq!(quantize_256(255, 255, 255), 231);
+        // Neutrale Töne landen in der Graustufenrampe
+        assert_eq!(quantize_256(128, 128, 128), 244);
+        assert_eq!(quantize_256(0x39, 0xc4, 0xb6), 79);
+    }
+
+    #[test]
+    fn quantize_16_maps_known_colors() {
+        assert_eq!(quantize_16(0, 0, 0), 0);
+        assert_eq!(quantize_16(200, 10, 10), 1);
+        assert_eq!(quantize_16(255, 0, 0), 9);
+        assert_eq!(quantize_16(90, 90, 250), 12);
+        assert_eq!(quantize_16(250, 250, 250), 15);
+        assert_eq!(
+            ColorDepth::Ansi16.quantize(rgb(0, 250, 0)),
+            Color::AnsiValue(10)
+        );
+        assert_eq!(ColorDepth::Truecolor.quantize(rgb(1, 2, 3)), rgb(1, 2, 3));
+    }
+
+    #[test]
+    fn sample_n_covers_both_ends() {
+        let set = ColorSet::from_hex(&["#ff0000", "#00ff00", "#0000ff"]);
+        assert_eq!(set.sample_n(0), Vec::new());
+        assert_eq!(set.sample_n(1), vec![rgb(255, 0, 0)]);
+        assert_eq!(set.sample_n(2), vec![rgb(255, 0, 0), rgb(0, 0, 255)]);
+        assert_eq!(set.sample_n(3)[1], set.gradient_color(0.5));
+    }
+
+    #[test]
+    fn builtin_sets_are_the_documented_four() {
+        let names: Vec<&str> = builtin_sets().iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["determination", "city", "2077", "thermography"]);
+        let (_, city) = &builtin_sets()[1];
+        assert_eq!(city.color_at_ratio(0.0), rgb(0xff, 0x06, 0x77));
+        assert_eq!(city.color_at_ratio(0.3), city.gradient_color(0.3));
+    }
+
+    #[test]
+    fn malformed_hex_is_an_error_not_a_panic() {
+        assert_eq!(
+            hex_to_color("#gg0000"),
+            Err(ParseColorError::InvalidDigit('g'))
+        );
+        // Mehrbytige Zeichen dürfen beim Zerlegen nicht mitten in einem UTF-8-Zeichen schneiden
+        assert!(hex_to_color("#ä00000").is_err());
+        assert!(hex_to_color("").is_err());
+        assert_eq!(
+            ParseColorError::InvalidDigit('g').to_string(),
+            "`g` ist keine Hexadezimalziffer"
+        );
+
+        let err = ColorSet::try_from_hex(&["#ff0000", "#12", "#zz0000"]).unwrap_err();
+        assert_eq!(err, ParseColorError::InvalidLength(2));
+        // `from_hex` überspringt ungültige Einträge stattdessen
+        assert_eq!(
+            ColorSet::from_hex(&["#ff0000", "#gg0000"]).sample_n(1),
+            vec![rgb(255, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn brightness_pushes_toward_white() {
+        let set = ColorSet::from_hex(&["#204000", "#40a020"]);
+        let brighter = set.clone().with_brightness(0.25);
+        for t in [0.0, 0.5, 1.0] {
+            let (r, g, b) = color_to_rgb(set.gradient_color(t));
+            let (br, bg, bb) = color_to_rgb(brighter.gradient_color(t));
+            assert!(br > r && bg > g && bb > b, "t={t}");
+        }
+        let white = set.with_brightness(1.0);
+        assert_eq!(
+            white.gradient_color(0.3),
+            Color::Rgb {
+                r: 255,
+                g: 255,
+                b: 255
+            }
+        );
+    }
+
+    #[test]
+    fn zero_contrast_gives_mid_grey() {
+        let grey = ColorSet::from_hex(&["#003300", "#00ff66"]).with_contrast(0.0);
+        for t in [0.0, 0.5, 1.0] {
+            assert_eq!(
+                grey.gradient_color(t),
+                Color::Rgb {
+                    r: 128,
+                    g: 128,
+                    b: 128
+                }
+            );
+        }
+    }
+}
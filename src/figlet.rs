@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// Zeichen, die jede FIGlet-Schrift in dieser Reihenfolge enthalten muss:
+/// ASCII 32–126, danach die sieben deutschen Sonderzeichen (optional).
+const REQUIRED: std::ops::RangeInclusive<u32> = 32..=126;
+const GERMAN: [char; 7] = ['Ä', 'Ö', 'Ü', 'ä', 'ö', 'ü', 'ß'];
+
+/// Fehler beim Laden einer `.flf`-Datei.
+#[derive(Debug)]
+pub enum FigletError {
+    Io(std::io::Error),
+    /// Kopfzeile fehlt, beginnt nicht mit `flf2a` oder hat ungültige Parameter
+    Header,
+    /// Die Datei endet, bevor das Zeichen vollständig gelesen wurde
+    Truncated(char),
+}
+
+impl fmt::Display for FigletError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FigletError::Io(err) => write!(f, "FIGlet-Schrift nicht lesbar: {err}"),
+            FigletError::Header => write!(f, "keine gültige FIGlet-Schrift (Kopfzeile)"),
+            FigletError::Truncated(ch) => {
+                write!(f, "FIGlet-Schrift unvollständig bei Zeichen `{ch}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FigletError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FigletError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for FigletError {
+    fn from(err: std::io::Error) -> Self {
+        FigletError::Io(err)
+    }
+}
+
+/// Eine geladene FIGlet-Schrift (`.flf`), gesetzt ohne Smushing.
+#[derive(Clone, Debug)]
+pub struct FigletFont {
+    height: usize,
+    glyphs: HashMap<char, Vec<String>>,
+}
+
+impl FigletFont {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FigletError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parst den Inhalt einer `.flf`-Datei.
+    pub fn parse(src: &str) -> Result<Self, FigletError> {
+        let mut lines = src.lines();
+        let header = lines.next().ok_or(FigletError::Header)?;
+        let rest = header.strip_prefix("flf2a").ok_or(FigletError::Header)?;
+        let mut chars = rest.chars();
+        let hardblank = chars.next().ok_or(FigletError::Header)?;
+        // old_layout darf negativ sein, daher vorzeichenbehaftet parsen
+        let params: Vec<i64> = chars
+            .as_str()
+            .split_whitespace()
+            .take(5)
+            .map(|p| p.parse().map_err(|_| FigletError::Header))
+            .collect::<Result<_, _>>()?;
+        let (height, comment_lines) = match params.as_slice() {
+            [height, _baseline, _max_len, _old_layout, comments]
+                if *height > 0 && *comments >= 0 =>
+            {
+                (*height as usize, *comments as usize)
+            }
+            _ => return Err(FigletError::Header),
+        };
+        let mut lines = lines.skip(comment_lines);
+
+        let mut glyphs = HashMap::new();
+        for code in REQUIRED {
+            let ch = char::from_u32(code).unwrap_or(' ');
+            let glyph =
+                read_glyph(&mut lines, height, hardblank).ok_or(FigletError::Truncated(ch))?;
+            glyphs.insert(ch, glyph);
+        }
+        // Deutsche Zeichen sind in älteren Schriften nicht immer enthalten
+        for ch in GERMAN {
+            match read_glyph(&mut lines, height, hardblank) {
+                Some(glyph) => glyphs.insert(ch, glyph),
+                None => break,
+            };
+        }
+
+        Ok(Self { height, glyphs })
+    }
+
+    /// Zeilenhöhe laut Kopfzeile
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Setzt den Text Glyphe an Glyphe; fehlende Zeichen werden ausgelassen.
+    pub(crate) fn render(&self, input: &str) -> Vec<String> {
+        let mut rows = vec![String::new(); self.height];
+        for glyph in input.chars().filter_map(|ch| self.glyphs.get(&ch)) {
+            for (row, part) in rows.iter_mut().zip(glyph) {
+                row.push_str(part);
+            }
+        }
+        rows
+    }
+}
+
+/// Liest `height` Unterzeilen und entfernt die Endmarkierungen.
+fn read_glyph<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    height: usize,
+    hardblank: char,
+) -> Option<Vec<String>> {
+    let mut glyph = Vec::with_capacity(height);
+    for _ in 0..height {
+        let line = lines.next()?.trim_end_matches(['\r', ' ']);
+        let endmark = line.chars().last()?;
+        let line = line.trim_end_matches(endmark).replace(hardblank, " ");
+        glyph.push(line);
+    }
+    // Unterzeilen auf gleiche Breite bringen, sonst verrutschen Folgezeichen
+    let width = glyph.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    for line in glyph.iter_mut() {
+        let missing = width - line.chars().count();
+        line.extend(std::iter::repeat_n(' ', missing));
+    }
+    Some(glyph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Zweizeilige Schrift: oben das Zeichen samt Hardblank, unten zwei Hardblanks
+    fn font_src(chars: impl Iterator<Item = char>) -> String {
+        let mut src = String::from("flf2a$ 2 1 4 0 1\nKommentar\n");
+        for ch in chars {
+            src.push_str(&format!("{ch}$@\n$$@@\n"));
+        }
+        src
+    }
+
+    #[test]
+    fn parse_renders_glyph_by_glyph() {
+        let src = font_src(REQUIRED.filter_map(char::from_u32));
+        let font = FigletFont::parse(&src).unwrap();
+        assert_eq!(font.height(), 2);
+        // Hardblanks werden zu Leerzeichen, ohne deutsche Zeichen fehlt `ä`
+        assert_eq!(font.render("Aäb"), ["A b ", "    "]);
+    }
+
+    #[test]
+    fn missing_glyphs_and_bad_headers_are_errors() {
+        let src = font_src(
+            REQUIRED
+                .filter_map(char::from_u32)
+                .take_while(|&c| c != 'x'),
+        );
+        assert!(matches!(
+            FigletFont::parse(&src),
+            Err(FigletError::Truncated('x'))
+        ));
+        assert!(matches!(
+            FigletFont::parse("flf2b$ 2 1 4 0 0"),
+            Err(FigletError::Header)
+        ));
+    }
+}
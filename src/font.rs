@@ -0,0 +1,411 @@
+use clap::ValueEnum;
+use unicode_width::UnicodeWidthChar;
+
+/// Integrierte Bitmap-Schriften für den Ziel-Text.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Font {
+    /// Kompakte 3x5-Schrift
+    #[default]
+    #[value(name = "3x5")]
+    F3x5,
+    /// Größere, besser lesbare 5x7-Schrift
+    #[value(name = "5x7")]
+    F5x7,
+}
+
+impl Font {
+    /// Glyphenbreite in Zellen
+    pub fn width(self) -> usize {
+        match self {
+            Font::F3x5 => 3,
+            Font::F5x7 => 5,
+        }
+    }
+
+    /// Glyphenhöhe in Zeilen
+    pub fn height(self) -> usize {
+        match self {
+            Font::F3x5 => 5,
+            Font::F5x7 => 7,
+        }
+    }
+}
+
+/// Setzt den Text als Banner in der gewählten Schrift, eine Zeichenkette pro
+/// Pixelzeile (`#` gesetzt, Leerzeichen frei) und eine Leerspalte zwischen zwei
+/// Glyphen; ohne sichtbare Zeichen leer.
+///
+/// ```
+/// use matrix::{Font, render_text};
+///
+/// let rows = render_text("HI", Font::F3x5);
+/// assert_eq!(rows, [
+///     "# # ###",
+///     "# #  # ",
+///     "###  # ",
+///     "# #  # ",
+///     "# # ###",
+/// ]);
+/// assert_eq!(rows.len(), Font::F3x5.height());
+/// assert_eq!(render_text("HI", Font::F5x7).len(), Font::F5x7.height());
+/// ```
+pub fn render_text(input: &str, font: Font) -> Vec<String> {
+    render_text_spaced(input, font, 1)
+}
+
+/// Wie [`render_text`], aber mit `spacing` Leerspalten zwischen zwei Glyphen.
+pub fn render_text_spaced(input: &str, font: Font, spacing: usize) -> Vec<String> {
+    let mut rows = vec![String::new(); font.height()];
+
+    // Zeichen ohne Breite (Joiner, Kombinationszeichen) erzeugen keine eigene Glyphe
+    for (i, ch) in input
+        .chars()
+        .filter(|c| c.width().unwrap_or(0) > 0)
+        .enumerate()
+    {
+        if i > 0 {
+            for row in rows.iter_mut() {
+                row.extend(std::iter::repeat_n(' ', spacing));
+            }
+        }
+        match font {
+            Font::F3x5 => push_glyph(&mut rows, &glyph_3x5(ch)),
+            Font::F5x7 => push_glyph(&mut rows, &glyph_5x7(ch)),
+        }
+    }
+
+    while rows.last().is_some_and(|r| r.is_empty()) {
+        rows.pop();
+    }
+
+    rows
+}
+
+fn push_glyph(rows: &mut [String], glyph: &[&str]) {
+    for (row, pattern) in rows.iter_mut().zip(glyph.iter()) {
+        row.push_str(pattern);
+    }
+}
+
+fn glyph_3x5(ch: char) -> [&'static str; 5] {
+    match ch {
+        'a' => ["   ", "   ", " ##", "# #", " ##"],
+        'b' => ["#  ", "#  ", "## ", "# #", "## "],
+        'c' => ["   ", "   ", " ##", "#  ", " ##"],
+        'd' => ["  #", "  #", " ##", "# #", " ##"],
+        'e' => ["   ", " # ", "# #", "## ", " ##"],
+        'f' => [" ##", " # ", "###", " # ", " # "],
+        'g' => ["   ", " ##", "# #", " ##", "## "],
+        'h' => ["#  ", "#  ", "## ", "# #", "# #"],
+        'i' => [" # ", "   ", " # ", " # ", " # "],
+        'j' => ["  #", "   ", "  #", "# #", " # "],
+        'k' => ["#  ", "#  ", "# #", "## ", "# #"],
+        'l' => [" # ", " # ", " # ", " # ", " ##"],
+        'm' => ["   ", "   ", "###", "###", "# #"],
+        'n' => ["   ", "   ", "## ", "# #", "# #"],
+        'o' => ["   ", "   ", " # ", "# #", " # "],
+        'p' => ["   ", "## ", "# #", "## ", "#  "],
+        'q' => ["   ", " ##", "# #", " ##", "  #"],
+        'r' => ["   ", "   ", "# #", "## ", "#  "],
+        's' => ["   ", "   ", " ##", " # ", "## "],
+        't' => [" # ", " # ", "###", " # ", " ##"],
+        'u' => ["   ", "   ", "# #", "# #", " ##"],
+        'v' => ["   ", "   ", "# #", "# #", " # "],
+        'w' => ["   ", "   ", "# #", "###", "###"],
+        'x' => ["   ", "   ", "# #", " # ", "# #"],
+        'y' => ["   ", "# #", " ##", "  #", "## "],
+        'z' => ["   ", "   ", "## ", " # ", " ##"],
+        'ä' => ["# #", "   ", " ##", "# #", " ##"],
+        'ö' => ["# #", "   ", " # ", "# #", " # "],
+        'ü' => ["# #", "   ", "# #", "# #", " ##"],
+        'ß' => [" # ", "# #", "## ", "# #", "## "],
+        'é' => ["  #", " # ", "# #", "## ", " ##"],
+        'è' => ["#  ", " # ", "# #", "## ", " ##"],
+        'à' => ["#  ", " # ", " ##", "# #", " ##"],
+        // Ohne eigene Kleinbuchstaben-Glyphe auf Großbuchstaben zurückfallen
+        _ => glyph_3x5_upper(ch.to_ascii_uppercase()),
+    }
+}
+
+fn glyph_3x5_upper(ch: char) -> [&'static str; 5] {
+    match ch {
+        'A' => ["###", "# #", "###", "# #", "# #"],
+        'B' => ["## ", "# #", "## ", "# #", "## "],
+        'C' => ["###", "#  ", "#  ", "#  ", "###"],
+        'D' => ["## ", "# #", "# #", "# #", "## "],
+        'E' => ["###", "#  ", "###", "#  ", "###"],
+        'F' => ["###", "#  ", "###", "#  ", "#  "],
+        'G' => ["###", "#  ", "# #", "# #", "###"],
+        'H' => ["# #", "# #", "###", "# #", "# #"],
+        'I' => ["###", " # ", " # ", " # ", "###"],
+        'J' => ["###", "  #", "  #", "# #", "###"],
+        'K' => ["# #", "## ", "#  ", "## ", "# #"],
+        'L' => ["#  ", "#  ", "#  ", "#  ", "###"],
+        'M' => ["# #", "###", "###", "# #", "# #"],
+        'N' => ["# #", "###", "###", "###", "# #"],
+        'O' => ["###", "# #", "# #", "# #", "###"],
+        'P' => ["###", "# #", "###", "#  ", "#  "],
+        'Q' => ["###", "# #", "# #", "###", "  #"],
+        'R' => ["###", "# #", "###", "## ", "# #"],
+        'S' => ["###", "#  ", "###", "  #", "###"],
+        'T' => ["###", " # ", " # ", " # ", " # "],
+        'U' => ["# #", "# #", "# #", "# #", "###"],
+        'V' => ["# #", "# #", "# #", "# #", " # "],
+        'W' => ["# #", "# #", "###", "###", "# #"],
+        'X' => ["# #", "# #", " # ", "# #", "# #"],
+        'Y' => ["# #", "# #", " # ", " # ", " # "],
+        'Z' => ["###", "  #", " # ", "#  ", "###"],
+        'Ä' => ["# #", " # ", "# #", "###", "# #"],
+        'Ö' => ["# #", "###", "# #", "# #", "###"],
+        'Ü' => ["# #", "   ", "# #", "# #", "###"],
+        'É' => ["  #", "###", "## ", "#  ", "###"],
+        'È' => ["#  ", "###", "## ", "#  ", "###"],
+        'À' => ["#  ", " # ", "# #", "###", "# #"],
+        '0' => ["###", "# #", "# #", "# #", "###"],
+        '1' => [" # ", "## ", " # ", " # ", "###"],
+        '2' => ["###", "  #", "###", "#  ", "###"],
+        '3' => ["###", "  #", "###", "  #", "###"],
+        '4' => ["# #", "# #", "###", "  #", "  #"],
+        '5' => ["###", "#  ", "###", "  #", "###"],
+        '6' => ["###", "#  ", "###", "# #", "###"],
+        '7' => ["###", "  #", " # ", " # ", " # "],
+        '8' => ["###", "# #", "###", "# #", "###"],
+        '9' => ["###", "# #", "###", "  #", "###"],
+        '!' => [" # ", " # ", " # ", "   ", " # "],
+        '?' => ["###", "  #", " # ", "   ", " # "],
+        '.' => ["   ", "   ", "   ", "   ", " # "],
+        ',' => ["   ", "   ", "   ", " # ", "#  "],
+        '-' => ["   ", "   ", "###", "   ", "   "],
+        '_' => ["   ", "   ", "   ", "   ", "###"],
+        ':' => ["   ", " # ", "   ", " # ", "   "],
+        '/' => ["  #", "  #", " # ", "#  ", "#  "],
+        '(' => ["  #", " # ", " # ", " # ", "  #"],
+        ')' => ["#  ", " # ", " # ", " # ", "#  "],
+        '[' => [" ##", " # ", " # ", " # ", " ##"],
+        ']' => ["## ", " # ", " # ", " # ", "## "],
+        '{' => [" ##", " # ", "#  ", " # ", " ##"],
+        '}' => ["## ", " # ", "  #", " # ", "## "],
+        '<' => ["   ", "  #", "#  ", "  #", "   "],
+        '>' => ["   ", "#  ", "  #", "#  ", "   "],
+        '+' => ["   ", " # ", "###", " # ", "   "],
+        '=' => ["   ", "###", "   ", "###", "   "],
+        '*' => ["   ", "# #", " # ", "# #", "   "],
+        '@' => [" # ", "# #", "###", "#  ", " ##"],
+        '#' => ["# #", "###", "# #", "###", "# #"],
+        '&' => [" # ", "# #", " # ", "# #", " ##"],
+        '%' => ["# #", "  #", " # ", "#  ", "# #"],
+        ' ' => ["   ", "   ", "   ", "   ", "   "],
+        // Unbekannte Zeichen als schlankes Fragezeichen statt als Vollblock
+        _ => ["## ", "  #", " # ", "   ", " # "],
+    }
+}
+
+fn glyph_5x7(ch: char) -> [&'static str; 7] {
+    match ch.to_ascii_uppercase() {
+        'A' => [
+            " ### ", "#   #", "#   #", "#####", "#   #", "#   #", "#   #",
+        ],
+        'B' => [
+            "#### ", "#   #", "#   #", "#### ", "#   #", "#   #", "#### ",
+        ],
+        'C' => [
+            " ### ", "#   #", "#    ", "#    ", "#    ", "#   #", " ### ",
+        ],
+        'D' => [
+            "#### ", "#   #", "#   #", "#   #", "#   #", "#   #", "#### ",
+        ],
+        'E' => [
+            "#####", "#    ", "#    ", "#### ", "#    ", "#    ", "#####",
+        ],
+        'F' => [
+            "#####", "#    ", "#    ", "#### ", "#    ", "#    ", "#    ",
+        ],
+        'G' => [
+            " ### ", "#   #", "#    ", "# ###", "#   #", "#   #", " ####",
+        ],
+        'H' => [
+            "#   #", "#   #", "#   #", "#####", "#   #", "#   #", "#   #",
+        ],
+        'I' => [
+            " ### ", "  #  ", "  #  ", "  #  ", "  #  ", "  #  ", " ### ",
+        ],
+        'J' => [
+            "  ###", "   # ", "   # ", "   # ", "   # ", "#  # ", " ##  ",
+        ],
+        'K' => [
+            "#   #", "#  # ", "# #  ", "##   ", "# #  ", "#  # ", "#   #",
+        ],
+        'L' => [
+            "#    ", "#    ", "#    ", "#    ", "#    ", "#    ", "#####",
+        ],
+        'M' => [
+            "#   #", "## ##", "# # #", "# # #", "#   #", "#   #", "#   #",
+        ],
+        'N' => [
+            "#   #", "#   #", "##  #", "# # #", "#  ##", "#   #", "#   #",
+        ],
+        'O' => [
+            " ### ", "#   #", "#   #", "#   #", "#   #", "#   #", " ### ",
+        ],
+        'P' => [
+            "#### ", "#   #", "#   #", "#### ", "#    ", "#    ", "#    ",
+        ],
+        'Q' => [
+            " ### ", "#   #", "#   #", "#   #", "# # #", "#  # ", " ## #",
+        ],
+        'R' => [
+            "#### ", "#   #", "#   #", "#### ", "# #  ", "#  # ", "#   #",
+        ],
+        'S' => [
+            " ####", "#    ", "#    ", " ### ", "    #", "    #", "#### ",
+        ],
+        'T' => [
+            "#####", "  #  ", "  #  ", "  #  ", "  #  ", "  #  ", "  #  ",
+        ],
+        'U' => [
+            "#   #", "#   #", "#   #", "#   #", "#   #", "#   #", " ### ",
+        ],
+        'V' => [
+            "#   #", "#   #", "#   #", "#   #", "#   #", " # # ", "  #  ",
+        ],
+        'W' => [
+            "#   #", "#   #", "#   #", "# # #", "# # #", "# # #", " # # ",
+        ],
+        'X' => [
+            "#   #", "#   #", " # # ", "  #  ", " # # ", "#   #", "#   #",
+        ],
+        'Y' => [
+            "#   #", "#   #", " # # ", "  #  ", "  #  ", "  #  ", "  #  ",
+        ],
+        'Z' => [
+            "#####", "    #", "   # ", "  #  ", " #   ", "#    ", "#####",
+        ],
+        '0' => [
+            " ### ", "#   #", "#  ##", "# # #", "##  #", "#   #", " ### ",
+        ],
+        '1' => [
+            "  #  ", " ##  ", "  #  ", "  #  ", "  #  ", "  #  ", " ### ",
+        ],
+        '2' => [
+            " ### ", "#   #", "    #", "   # ", "  #  ", " #   ", "#####",
+        ],
+        '3' => [
+            "#####", "   # ", "  #  ", "   # ", "    #", "#   #", " ### ",
+        ],
+        '4' => [
+            "   # ", "  ## ", " # # ", "#  # ", "#####", "   # ", "   # ",
+        ],
+        '5' => [
+            "#####", "#    ", "#### ", "    #", "    #", "#   #", " ### ",
+        ],
+        '6' => [
+            "  ## ", " #   ", "#    ", "#### ", "#   #", "#   #", " ### ",
+        ],
+        '7' => [
+            "#####", "    #", "   # ", "  #  ", " #   ", " #   ", " #   ",
+        ],
+        '8' => [
+            " ### ", "#   #", "#   #", " ### ", "#   #", "#   #", " ### ",
+        ],
+        '9' => [
+            " ### ", "#   #", "#   #", " ####", "    #", "   # ", " ##  ",
+        ],
+        '!' => [
+            "  #  ", "  #  ", "  #  ", "  #  ", "  #  ", "     ", "  #  ",
+        ],
+        '?' => [
+            " ### ", "#   #", "    #", "   # ", "  #  ", "     ", "  #  ",
+        ],
+        '.' => [
+            "     ", "     ", "     ", "     ", "     ", " ##  ", " ##  ",
+        ],
+        ',' => [
+            "     ", "     ", "     ", "     ", " ##  ", "  #  ", " #   ",
+        ],
+        '-' => [
+            "     ", "     ", "     ", "#####", "     ", "     ", "     ",
+        ],
+        '_' => [
+            "     ", "     ", "     ", "     ", "     ", "     ", "#####",
+        ],
+        ':' => [
+            "     ", " ##  ", " ##  ", "     ", " ##  ", " ##  ", "     ",
+        ],
+        '/' => [
+            "    #", "    #", "   # ", "  #  ", " #   ", "#    ", "#    ",
+        ],
+        ' ' => [
+            "     ", "     ", "     ", "     ", "     ", "     ", "     ",
+        ],
+        _ => UNKNOWN_5X7,
+    }
+}
+
+/// Ersatzglyphe der 5x7-Schrift für Zeichen ohne eigene Form
+const UNKNOWN_5X7: [&str; 7] = [
+    "#####", "#   #", "# # #", "#   #", "# # #", "#   #", "#####",
+];
+
+/// 5x7-Glyphe für eine einzelne Zelle; `None`, wenn die Schrift das Zeichen nicht kennt.
+pub(crate) fn bitmap_5x7(ch: char) -> Option<[&'static str; 7]> {
+    let glyph = glyph_5x7(ch);
+    (glyph != UNKNOWN_5X7).then_some(glyph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_5x7_has_seven_rows() {
+        let rows = render_text("AB", Font::F5x7);
+        assert_eq!(rows.len(), 7);
+        // Zwei Glyphen zu fünf Spalten und eine Leerspalte
+        assert!(rows.iter().all(|row| row.chars().count() == 11));
+        assert_eq!(rows[0], " ###  #### ");
+    }
+
+    #[test]
+    fn lowercase_has_own_glyphs() {
+        assert_ne!(glyph_3x5('a'), glyph_3x5('A'));
+        assert_ne!(glyph_3x5('z'), glyph_3x5('Z'));
+        // Großbuchstaben bleiben unverändert
+        assert_eq!(glyph_3x5('A'), glyph_3x5_upper('A'));
+    }
+
+    #[test]
+    fn umlauts_are_not_the_fallback() {
+        let fallback = glyph_3x5('\u{2603}');
+        for ch in ['ä', 'ö', 'ü', 'ß', 'é', 'è', 'à', 'Ä', 'Ö', 'Ü'] {
+            assert_ne!(glyph_3x5(ch), fallback, "{ch}");
+        }
+        assert!(glyph_3x5(' ').iter().all(|row| row.trim().is_empty()));
+    }
+
+    #[test]
+    fn letter_spacing_widens_by_one_column() {
+        let width = |spacing| {
+            render_text_spaced("AB", Font::F3x5, spacing)[0]
+                .chars()
+                .count()
+        };
+        assert_eq!(width(0) + 1, width(1));
+        assert_eq!(width(1) + 2, width(3));
+        // Ohne Abstand stoßen die Glyphen direkt aneinander
+        let rows = render_text_spaced("AB", Font::F3x5, 0);
+        assert_eq!(
+            rows[0],
+            format!("{}{}", glyph_3x5('A')[0], glyph_3x5('B')[0])
+        );
+    }
+
+    #[test]
+    fn code_symbols_have_own_glyphs() {
+        let fallback = glyph_3x5('\u{2603}');
+        for ch in "(){}[]<>+=*@#&%".chars() {
+            assert_ne!(glyph_3x5(ch), fallback, "{ch}");
+        }
+        // Spiegelbildliche Klammern unterscheiden sich
+        assert_ne!(glyph_3x5('('), glyph_3x5(')'));
+    }
+}
@@ -0,0 +1,53 @@
+//! Retro-futuristischer Matrix-Digital-Rain als Bibliothek.
+//!
+//! [`RainState`] simuliert die fallenden Spalten samt zentriertem Ziel-Text und
+//! liefert pro Schritt ein [`Grid`] aus gestylten Zellen. Die Ausgabe auf ein
+//! Terminal (oder anderswohin) bleibt dem Aufrufer überlassen.
+//!
+//! ```
+//! use matrix::{RainConfig, RainState};
+//! use std::time::Duration;
+//!
+//! let config = RainConfig {
+//!     text: "HI".to_string(),
+//!     ..RainConfig::default()
+//! };
+//! let mut state = RainState::with_config(config, 40, 12);
+//! for _ in 0..10 {
+//!     state.step(Duration::from_millis(16));
+//! }
+//!
+//! let grid = state.grid();
+//! assert_eq!((grid.width(), grid.height()), (40, 12));
+//! let text: String = grid.rows().map(|row| row.iter().map(|c| c.ch).collect::<String>()).collect();
+//! assert!(text.contains('#'));
+//! ```
+
+mod charset;
+mod color;
+mod figlet;
+mod font;
+mod palette;
+mod rain;
+mod record;
+mod render;
+mod screenshot;
+
+pub use charset::{CharFilter, Charset};
+pub use color::{
+    BlendMode, ColorDepth, ColorSet, ColorSetName, ParseColorError, builtin_sets, hex_to_color,
+    hsv_to_color, quantize_16, quantize_256,
+};
+pub use crossterm::style::Color;
+pub use figlet::{FigletError, FigletFont};
+pub use font::{Font, render_text, render_text_spaced};
+pub use palette::{GimpPalette, GplError};
+pub use rain::{
+    BURST_RADIUS, BorderColor, BorderGlyphs, BorderStyle, CELEBRATION, CURSOR_BLINK, Cell,
+    DEPTH_SHADING, Direction, FrameHook, GRAVITY, Gradient, Grid, HEAD_BRIGHTEN, REVEAL_FADE,
+    RainConfig, RainState, Reveal, SHADING_RAMP, Style, TextAlign, TextBlock, TextHAlign,
+    WAKE_GLOW, rainbow_hue, shade_char,
+};
+pub use record::CastRecorder;
+pub use render::{CrosstermRenderer, RenderOptions, Renderer, TextRenderer};
+pub use screenshot::{PNG_CELL_HEIGHT, PNG_CELL_WIDTH, write_png};
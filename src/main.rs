@@ -1,474 +1,1884 @@
-use clap::{Parser, ValueEnum};
+mod profile;
+mod theme;
+
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgGroup, ArgMatches, CommandFactory, FromArgMatches, Parser, ValueEnum};
 use crossterm::{
-    ExecutableCommand, QueueableCommand, cursor,
-    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
-    style::{Color, PrintStyledContent, Stylize},
-    terminal::{self, ClearType},
+    ExecutableCommand, cursor,
+    event::{
+        self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
+    terminal,
+};
+use matrix::{
+    BlendMode, BorderColor, BorderStyle, CastRecorder, Cell, CharFilter, Charset, Color,
+    ColorDepth, ColorSet, ColorSetName, CrosstermRenderer, Direction, FigletFont, Font,
+    GimpPalette, Gradient, Grid, RainConfig, RainState, RenderOptions, Renderer, Reveal, Style,
+    TextAlign, TextBlock, TextHAlign, TextRenderer, builtin_sets, hex_to_color, write_png,
 };
-use rand::Rng;
-use std::cmp::min;
-use std::io::{Write, stdout};
+use profile::Profile;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufWriter, Read, Stdout, Write, stdout};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 use std::time::{Duration, Instant};
+use theme::Theme;
+use unicode_width::UnicodeWidthChar;
+
+// Listen, die ein eigener `value_parser` aus einem einzigen Argument erzeugt (--colors,
+// --charset-chars, --charset-weights), sind als `std::vec::Vec` geschrieben: ein bloßes
+// `Vec` hielte clap für ein mehrfach angebbares Argument und gäbe die Werte einzeln weiter.
+#[derive(Parser, Debug)]
+#[command(
+    name = "matrix",
+    version,
+    about = "Retro-futuristischer Matrix-Digital-Rain in Rust",
+    group = ArgGroup::new("headless").args(["output", "screenshot"]).multiple(true),
+    group = ArgGroup::new("length").args(["frames", "duration"]).multiple(true)
+)]
+struct Args {
+    /// Profil-Datei (TOML); ohne Angabe wird ~/.config/matrixrain/config.toml gelesen, falls vorhanden.
+    /// Flags auf der Kommandozeile haben Vorrang vor Werten aus dem Profil
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Ziel-String, in dem die fallenden Zeichen „einrasten“; `-` liest ihn von stdin.
+    /// Mehrfach angegeben wird jeder String ein eigener Block mit eigenem Rahmen
+    #[arg(short, long, default_value = "Hallo Welt!")]
+    string: Vec<String>,
+
+    /// Ziel-String aus einer Datei lesen; mehrere Zeilen werden gestapelt
+    #[arg(long, value_name = "PATH", conflicts_with = "string")]
+    string_file: Option<PathBuf>,
+
+    /// Schrift für den Ziel-String: 3x5, 5x7
+    #[arg(long, value_enum, default_value_t = Font::F3x5)]
+    font: Font,
+
+    /// Leerspalten zwischen den Glyphen der integrierten Schrift (0 = aneinander)
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u16).range(0..=16))]
+    letter_spacing: u16,
+
+    /// Leerzeilen zwischen den Zeilen eines mehrzeiligen Ziel-Strings
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u16).range(0..=16))]
+    line_spacing: u16,
+
+    /// FIGlet-Schrift (.flf) für den Ziel-String statt der integrierten Schrift
+    #[arg(long, conflicts_with = "font")]
+    figlet: Option<PathBuf>,
+
+    /// Senkrechte Lage des Ziel-Strings: top, center, bottom oder row:<N> (oberste Textzeile
+    /// in Bildschirmzeile N, begrenzt auf gültige Zeilen). Die n-te Angabe gilt für den
+    /// n-ten --string, Strings ohne Angabe stehen mittig
+    #[arg(long, value_name = "top|center|bottom|row:N", default_value = "center",
+          value_parser = parse_text_align)]
+    text_align: Vec<TextAlign>,
+
+    /// Waagerechte Lage des Ziel-Strings: left, center, right; wie --text-align je --string
+    #[arg(long, value_enum, default_value = "center")]
+    text_halign: Vec<TextHAlign>,
+
+    /// Darstellung: simple (nur Regen), block (Regen um gerahmten Text), aged (Farbe nach Zellalter)
+    #[arg(long, value_enum, default_value_t = Style::Block)]
+    style: Style,
+
+    /// Thema als Voreinstellung für Zeichensatz, Farbset und Rahmen: classic (Katakana, grün),
+    /// cyberpunk (2077, doppelter Rahmen), thermal (thermography, binär); einzelne Flags gehen vor
+    #[arg(long, value_enum)]
+    theme: Option<Theme>,
+
+    /// Farbset: determination, city, 2077, thermography
+    #[arg(short, long, value_enum)]
+    colorset: Option<ColorSetName>,
+
+    /// Eigenes Farbset als kommagetrennte Hex-Liste, z. B. "#ff0000,#00ff00,#0000ff"
+    #[arg(long, value_parser = parse_hex_list, action = clap::ArgAction::Set,
+          conflicts_with_all = ["colorset", "list"])]
+    colors: Option<std::vec::Vec<String>>,
+
+    /// Farbset aus einer GIMP-Palette (.gpl); ungültige Farbzeilen werden mit Warnung übersprungen
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["colorset", "colors", "list"])]
+    palette_file: Option<PathBuf>,
+
+    /// Kopffarbe eines eigenen Zwei-Farben-Verlaufs nur für die Spuren (mit --tail-color);
+    /// Ziel-Text und Rahmen behalten die Farben des Farbsets
+    #[arg(long, value_name = "HEX", value_parser = parse_color, requires = "tail_color",
+          conflicts_with = "rainbow")]
+    head_color: Option<Color>,
+
+    /// Schwanzfarbe des Spurverlaufs (mit --head-color)
+    #[arg(long, value_name = "HEX", value_parser = parse_color, requires = "head_color")]
+    tail_color: Option<Color>,
+
+    /// Verlauf entlang der Spur umdrehen: dunkler Kopf, helles Spurende
+    #[arg(long)]
+    reverse_gradient: bool,
+
+    /// Farbraum für Verläufe: srgb, linear, oklab
+    #[arg(long, value_enum, default_value_t = BlendMode::Srgb)]
+    blend: BlendMode,
+
+    /// Helligkeit des Farbsets von -1.0 (schwarz) bis 1.0 (weiß), 0 = unverändert
+    #[arg(long, value_name = "-1..1", allow_negative_numbers = true, value_parser = parse_brightness)]
+    brightness: Option<f32>,
+
+    /// Kontrast des Farbsets von 0.0 (einheitliches Grau) bis 2.0, 1 = unverändert
+    #[arg(long, value_name = "0..2", value_parser = parse_contrast)]
+    contrast: Option<f32>,
+
+    /// Liste der verfügbaren Farbsets anzeigen und beenden
+    #[arg(long, conflicts_with = "colorset")]
+    list: bool,
+
+    /// Wirksame Optionen nach Profil, Thema und Kommandozeile als TOML ausgeben und beenden;
+    /// die Ausgabe lässt sich als Profil-Datei verwenden
+    #[arg(long)]
+    print_config: bool,
+
+    /// Hintergrund-Verschiebungsgeschwindigkeit (0-10)
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u8).range(0..=10))]
+    scroll_speed: u8,
+
+    /// Laufschrift: der Ziel-String wandert mit dieser Geschwindigkeit (1-10) nach links und
+    /// läuft rechts wieder ein, vollständig beleuchtet, ohne Rahmen und ohne Einloggen
+    #[arg(long, value_name = "SPEED", conflicts_with = "invert",
+          value_parser = clap::value_parser!(u8).range(1..=10))]
+    marquee: Option<u8>,
+
+    /// Spurlänge in Zellen inklusive Kopf (1-64)
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u16).range(1..=64))]
+    trail: u16,
+
+    /// Kleinste Spurlänge; jede Spur würfelt ihre Länge bis --trail-max bzw. --trail (1-64)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..=64))]
+    trail_min: Option<u16>,
+
+    /// Größte Spurlänge, Gegenstück zu --trail-min (1-64)
+    #[arg(long, conflicts_with = "trail", value_parser = clap::value_parser!(u16).range(1..=64))]
+    trail_max: Option<u16>,
+
+    /// Frames zwischen zwei Schritten der Zeichenrotation (0 = keine Rotation)
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    shimmer: u16,
+
+    /// Zeichensatz des Regens: ascii, katakana, binary
+    #[arg(long, value_enum, default_value_t = Charset::Ascii)]
+    charset: Charset,
+
+    /// Großbuchstaben aus --charset herausnehmen
+    #[arg(long, conflicts_with = "charset_chars")]
+    no_uppercase: bool,
+
+    /// Kleinbuchstaben aus --charset herausnehmen
+    #[arg(long, conflicts_with = "charset_chars")]
+    no_lowercase: bool,
+
+    /// Ziffern aus --charset herausnehmen; z. B. mit --no-uppercase --no-lowercase nur Ziffern
+    #[arg(long, conflicts_with = "charset_chars")]
+    no_digits: bool,
+
+    /// Eigene Zeichen für den Regen, z. B. "日本語012" (hat Vorrang vor --charset).
+    /// Breite Zeichen wie CJK belegen zwei Terminalzellen und können benachbarte Spalten überdecken.
+    #[arg(long, value_parser = parse_charset_chars, action = clap::ArgAction::Set)]
+    charset_chars: Option<std::vec::Vec<char>>,
 
-#[derive(Copy, Clone, Debug, ValueEnum)]
-enum ColorSetName {
-    Determination,
-    City,
-    #[value(name = "2077")]
-    C2077,
-    Thermography,
+    /// Gewichte einzelner Zeichen als kommagetrennte Liste ZEICHEN=GEWICHT, z. B. "0=9,1=1";
+    /// nicht genannte Zeichen wiegen 1, Gewicht 0 blendet ein Zeichen aus (aber nicht alle)
+    #[arg(long, value_name = "CHAR=WEIGHT,...", value_parser = parse_charset_weights)]
+    charset_weights: Option<std::vec::Vec<(char, u32)>>,
+
+    /// Helligkeit über die Zeichendichte ` .:-=+*#%@` statt über die Farbe zeigen (ersetzt den
+    /// Zeichensatz der Spuren; etwa zusammen mit --no-color)
+    #[arg(long)]
+    ascii_shading: bool,
+
+    /// Fließrichtung des Regens: down, up, left, right
+    #[arg(long, value_enum, default_value_t = Direction::Down)]
+    direction: Direction,
+
+    /// Ohne Farben ausgeben (auch über die Umgebungsvariable NO_COLOR)
+    #[arg(long)]
+    no_color: bool,
+
+    /// Farbtiefe: truecolor, 256, 16 (Standard: aus COLORTERM/TERM erkannt)
+    #[arg(long, value_enum)]
+    color_depth: Option<ColorDepth>,
+
+    /// Startwert für reproduzierbare Animationen
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Regendichte 0.0-1.0: Wahrscheinlichkeit, dass eine abgelaufene Spur neu startet.
+    /// 1.0 hält jede Spur ständig aktiv, darunter wird der Regen lichter
+    #[arg(long, default_value_t = 1.0, value_parser = parse_fraction)]
+    density: f32,
+
+    /// Abgelaufene Spuren starten sofort knapp über dem Rand neu, ohne Lücke; mit --density
+    /// unter 1.0 verblassen die Spurenden weiter, nur die Wartezeit entfällt
+    #[arg(long)]
+    continuous: bool,
+
+    /// Kürzeste Verweildauer eines Tropfens pro Zelle in Millisekunden (schnellste Spur)
+    #[arg(long, default_value_t = 40, value_parser = clap::value_parser!(u64).range(1..=10_000))]
+    speed_min: u64,
+
+    /// Längste Verweildauer eines Tropfens pro Zelle in Millisekunden (langsamste Spur)
+    #[arg(long, default_value_t = 120, value_parser = clap::value_parser!(u64).range(1..=10_000))]
+    speed_max: u64,
+
+    /// Zufälliger Versatz von ±MS Millisekunden auf jeden einzelnen Schritt einer Spur
+    #[arg(long, value_name = "MS", default_value_t = 0, value_parser = clap::value_parser!(u16).range(0..=1000))]
+    jitter: u16,
+
+    /// Schwerkraft: Tropfen werden im Fall schneller und starten nach dem Neustart langsam
+    #[arg(long)]
+    gravity: bool,
+
+    /// Spiegelung: die rechte Bildhälfte zeigt die linke seitenverkehrt (Ziel-Text bleibt lesbar)
+    #[arg(long)]
+    mirror: bool,
+
+    /// Kopf Richtung Weiß aufhellen; optional mit Stärke 0.0-1.0 (ohne Wert: 0.5)
+    #[arg(long, num_args = 0..=1, default_missing_value = "0.5", value_parser = parse_fraction)]
+    glow: Option<f32>,
+
+    /// Wahrscheinlichkeit 0.0-1.0, mit der Spurzellen zufällig das Zeichen wechseln
+    #[arg(long, default_value_t = 0.0, value_parser = parse_fraction)]
+    flicker: f32,
+
+    /// Erscheinen des Ziel-Texts: instant, fade (Überblendung), typewriter (in Lesereihenfolge)
+    #[arg(long, value_enum, default_value_t = Reveal::Instant)]
+    reveal: Reveal,
+
+    /// Deckkraft noch offener Zeichen des Ziel-Texts zwischen Hintergrund (0.0) und Weiß (1.0);
+    /// ohne Angabe dunkelgrau
+    #[arg(long, value_name = "ALPHA", value_parser = parse_fraction)]
+    ghost_alpha: Option<f32>,
+
+    /// Ziel-Text zeitgesteuert mit N Wörtern (à fünf Zeichen) pro Minute in Lesereihenfolge
+    /// einloggen, unabhängig von den Tropfen
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..=10_000))]
+    reveal_wpm: Option<u32>,
+
+    /// Blinkende Schreibmarke an der nächsten offenen Glyphenspalte (für --reveal typewriter
+    /// und --reveal-wpm)
+    #[arg(long)]
+    cursor: bool,
+
+    /// Eingeloggte Zeichen zeigen so viele Frames lang zufällige Zeichen des Regens, bevor
+    /// das echte stehen bleibt
+    #[arg(long, value_name = "FRAMES", conflicts_with = "marquee",
+          value_parser = clap::value_parser!(u8).range(1..))]
+    decrypt: Option<u8>,
+
+    /// Ist der Ziel-String vollständig, friert der Regen eine Sekunde lang ein und weiße Funken
+    /// blitzen um den Rahmen; --exit-on-complete wartet das Funkeln ab
+    #[arg(long, conflicts_with = "marquee")]
+    celebrate: bool,
+
+    /// Beenden, sobald der Ziel-Text vollständig eingeloggt ist (auch mit --frames)
+    #[arg(long)]
+    exit_on_complete: bool,
+
+    /// Wartezeit in Millisekunden nach dem vollständigen Einloggen vor dem Beenden
+    #[arg(long, default_value_t = 0, requires = "exit_on_complete")]
+    hold: u64,
+
+    /// Regenbogen: Farbton läuft in PERIOD Frames einmal über das HSV-Rad (ohne Wert: 600);
+    /// ersetzt das Farbset
+    #[arg(long, value_name = "PERIOD", num_args = 0..=1, default_missing_value = "600",
+          value_parser = clap::value_parser!(u32).range(1..))]
+    rainbow: Option<u32>,
+
+    /// Jeder Spur eine zufällige Farbtonverschiebung bis ±DEGREES geben (ohne Wert: 30)
+    #[arg(long, value_name = "DEGREES", num_args = 0..=1, default_missing_value = "30",
+          value_parser = parse_hue_offset)]
+    per_column_hue: Option<f32>,
+
+    /// Verlaufsrichtung: trail (entlang jeder Spur), screen (nach Zeile), column (nach Spalte)
+    #[arg(long, value_enum, default_value_t = Gradient::Trail)]
+    gradient: Gradient,
+
+    /// Umgekehrt: Regen nur innerhalb der Glyphen des Ziel-Texts, außerhalb dunkel
+    #[arg(long)]
+    invert: bool,
+
+    /// Vorlauf in Sekunden, in dem es ohne Rahmen und Ziel-Text regnet, bevor Zeichen einloggen
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    intro_delay: Option<Duration>,
+
+    /// Hintergrundschicht aus festen, kaum sichtbaren Zeichen in leeren Zellen; Helligkeit 0.0-1.0
+    #[arg(long, value_name = "BRIGHTNESS", value_parser = parse_fraction)]
+    ambient: Option<f32>,
+
+    /// Nachleuchten: Zellen hinter dem Kopf leuchten auf und behalten je Frame diesen Anteil (0.0-1.0)
+    #[arg(long, value_name = "FACTOR", value_parser = parse_fraction)]
+    wake: Option<f32>,
+
+    /// Tiefenwirkung: schnelle Spuren heller, langsame dunkler (siehe --speed-min/--speed-max)
+    #[arg(long)]
+    depth_shading: bool,
+
+    /// Kopf nicht fett, sondern nur heller zeichnen (für Schriften mit breiteren fetten Glyphen)
+    #[arg(long)]
+    no_bold_head: bool,
+
+    /// Nach so vielen Sekunden beenden und das Terminal wiederherstellen (0 = endlos);
+    /// begrenzt auch --frames/--screenshot auf die entsprechende Simulationszeit
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    duration: Option<Duration>,
+
+    /// Einblenden beim Start über so viele Sekunden, vom Hintergrund zu voller Helligkeit
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    fade_in: Option<Duration>,
+
+    /// Rahmen um den Ziel-Text: ascii, rounded, double, none
+    #[arg(long, value_enum, default_value_t = BorderStyle::Ascii)]
+    border: BorderStyle,
+
+    /// Rahmenfarbe als Hex (Standard: dunkelgrau) oder `auto` für die Mitte des Farbsets
+    #[arg(long, value_name = "HEX|auto", value_parser = parse_border_color)]
+    border_color: Option<BorderColor>,
+
+    /// Hintergrundfarbe als Hex hinter allen Zellen (Standard: Hintergrund des Terminals);
+    /// teiltransparente Farbset-Farben werden gegen sie gemischt
+    #[arg(long, value_name = "HEX", value_parser = parse_color)]
+    background: Option<Color>,
+
+    /// Füllzeichen für leere Zellen, z. B. "." für ein schwaches Punktraster (Standard: leer)
+    #[arg(long, value_name = "CHAR", value_parser = parse_empty_char)]
+    empty_char: Option<char>,
+
+    /// Farbe des Füllzeichens als Hex (Standard: dunkelgrau)
+    #[arg(long, value_name = "HEX", value_parser = parse_color, requires = "empty_char")]
+    empty_color: Option<Color>,
+
+    /// Beim Beenden die eingeloggten Zeichen des Ziel-Texts auf dem normalen Bildschirm
+    /// ausgeben, damit sie im Scrollback stehen bleiben; nur im interaktiven Terminal
+    #[arg(long, conflicts_with_all = ["frames", "output", "screenshot"])]
+    keep_text: bool,
+
+    /// Maus einfangen: ein Linksklick lässt um die Klickstelle neue Tropfen fallen
+    #[arg(long)]
+    mouse: bool,
+
+    /// Bildrate in Frames pro Sekunde (1-240; 0 wird abgelehnt)
+    #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u16).range(1..=240))]
+    fps: u16,
+
+    /// Ausgabe zusätzlich als asciinema-Aufnahme (asciicast v2) in diese Datei schreiben
+    #[arg(long, value_name = "FILE.cast")]
+    record: Option<PathBuf>,
+
+    /// Ohne Terminal rechnen und genau so viele Frames als Text ausgeben.
+    /// Frames werden durch Seitenvorschübe (\f) getrennt, der Zeitschritt ergibt sich aus --fps.
+    #[arg(long, requires = "headless", conflicts_with = "record")]
+    frames: Option<u32>,
+
+    /// Zieldatei für --frames bzw. --duration; `-` schreibt auf die Standardausgabe
+    #[arg(long, requires = "length")]
+    output: Option<PathBuf>,
+
+    /// Ohne Terminal rechnen und den letzten Frame als PNG speichern, 8x16 Pixel je Zelle
+    /// mit eingebauter Schrift statt der Terminalschrift. Ohne --frames nach 5 Sekunden
+    /// Simulationszeit
+    #[arg(long, value_name = "FILE.png", conflicts_with = "record")]
+    screenshot: Option<PathBuf>,
+
+    /// Breite in Zellen statt der erkannten Terminalgröße (Rückfall: 80)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    width: Option<u16>,
+
+    /// Höhe in Zellen statt der erkannten Terminalgröße (Rückfall: 24)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    height: Option<u16>,
 }
 
-#[derive(Clone, Debug)]
-struct ColorSet {
-    colors: Vec<Color>,
+/// Prüft eine kommagetrennte Hex-Liste und nennt das erste ungültige Element.
+fn parse_hex_list(s: &str) -> Result<Vec<String>, String> {
+    let hexes: Vec<String> = s.split(',').map(|h| h.trim().to_string()).collect();
+    for h in &hexes {
+        hex_to_color(h).map_err(|err| format!("ungültige Hex-Farbe `{h}`: {err}"))?;
+    }
+    Ok(hexes)
 }
 
-impl ColorSet {
-    fn from_name(name: ColorSetName) -> Self {
-        match name {
-            ColorSetName::Determination => Self::from_hex(&["#39c4b6", "#fee801", "#6300ff"]),
-            ColorSetName::City => Self::from_hex(&["#ff0677", "#0051ff", "#8900ff"]),
-            ColorSetName::C2077 => Self::from_hex(&["#c5003c", "#880425", "#f3e600", "#55ead4"]),
-            ColorSetName::Thermography => {
-                Self::from_hex(&["#ff004a", "#ffcc3d", "#ff5631", "#ad00ff"])
-            }
-        }
+/// Anteil zwischen 0.0 und 1.0, etwa für --density und --glow.
+fn parse_fraction(s: &str) -> Result<f32, String> {
+    match s.parse::<f32>() {
+        Ok(d) if (0.0..=1.0).contains(&d) => Ok(d),
+        Ok(_) => Err("Wert muss zwischen 0.0 und 1.0 liegen".to_string()),
+        Err(_) => Err(format!("`{s}` ist keine Zahl")),
     }
+}
 
-    fn from_hex(hexes: &[&str]) -> Self {
-        let mut colors = Vec::new();
-        for h in hexes {
-            if let Some(c) = hex_to_color(h) {
-                colors.push(c);
-            }
-        }
-        if colors.is_empty() {
-            colors.push(Color::Green);
-        }
-        Self { colors }
+/// Senkrechte Ausrichtung: `top`, `center`, `bottom` oder `row:<N>`.
+fn parse_text_align(s: &str) -> Result<TextAlign, String> {
+    match s {
+        "top" => Ok(TextAlign::Top),
+        "center" => Ok(TextAlign::Center),
+        "bottom" => Ok(TextAlign::Bottom),
+        _ => match s.strip_prefix("row:").map(str::parse::<u16>) {
+            Some(Ok(row)) => Ok(TextAlign::Row(row)),
+            Some(Err(_)) => Err(format!("`{s}`: Zeile muss eine Zahl ab 0 sein")),
+            None => Err(format!(
+                "`{s}` ist keine Ausrichtung; erlaubt: top, center, bottom, row:<N>"
+            )),
+        },
     }
+}
 
-    fn gradient_color(&self, t: f32) -> Color {
-        // t in [0,1], map über Palette
-        if self.colors.len() == 1 {
-            return self.colors[0];
-        }
-        let n = self.colors.len();
-        let scaled = t.clamp(0.0, 1.0) * (n as f32 - 1.0);
-        let i = scaled.floor() as usize;
-        let j = min(i + 1, n - 1);
-        let local_t = scaled - i as f32;
+/// Nicht-negative Sekunden, auch mit Nachkommastellen.
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    match s.parse::<f64>() {
+        Ok(secs) if secs.is_finite() && secs >= 0.0 => Ok(Duration::from_secs_f64(secs)),
+        Ok(_) => Err("Dauer darf nicht negativ sein".to_string()),
+        Err(_) => Err(format!("`{s}` ist keine Zahl")),
+    }
+}
 
-        blend_color(self.colors[i], self.colors[j], local_t)
+fn parse_brightness(s: &str) -> Result<f32, String> {
+    match s.parse::<f32>() {
+        Ok(d) if (-1.0..=1.0).contains(&d) => Ok(d),
+        Ok(_) => Err("Helligkeit muss zwischen -1.0 und 1.0 liegen".to_string()),
+        Err(_) => Err(format!("`{s}` ist keine Zahl")),
     }
 }
 
-fn hex_to_color(hex: &str) -> Option<Color> {
-    let h = hex.trim().trim_start_matches('#');
-    if h.len() != 6 {
-        return None;
+fn parse_contrast(s: &str) -> Result<f32, String> {
+    match s.parse::<f32>() {
+        Ok(d) if (0.0..=2.0).contains(&d) => Ok(d),
+        Ok(_) => Err("Kontrast muss zwischen 0.0 und 2.0 liegen".to_string()),
+        Err(_) => Err(format!("`{s}` ist keine Zahl")),
     }
-    let r = u8::from_str_radix(&h[0..2], 16).ok()?;
-    let g = u8::from_str_radix(&h[2..4], 16).ok()?;
-    let b = u8::from_str_radix(&h[4..6], 16).ok()?;
-    Some(Color::Rgb { r, g, b })
 }
 
-fn blend_color(a: Color, b: Color, t: f32) -> Color {
-    let (ar, ag, ab) = color_to_rgb(a);
-    let (br, bg, bb) = color_to_rgb(b);
-    let t = t.clamp(0.0, 1.0);
-    let r = (ar as f32 + (br as f32 - ar as f32) * t) as u8;
-    let g = (ag as f32 + (bg as f32 - ag as f32) * t) as u8;
-    let b = (ab as f32 + (bb as f32 - ab as f32) * t) as u8;
-    Color::Rgb { r, g, b }
+fn parse_hue_offset(s: &str) -> Result<f32, String> {
+    match s.parse::<f32>() {
+        Ok(d) if (0.0..=180.0).contains(&d) => Ok(d),
+        Ok(_) => Err("Farbtonverschiebung muss zwischen 0 und 180 Grad liegen".to_string()),
+        Err(_) => Err(format!("`{s}` ist keine Zahl")),
+    }
 }
 
-fn color_to_rgb(c: Color) -> (u8, u8, u8) {
-    match c {
-        Color::Rgb { r, g, b } => (r, g, b),
-        Color::Black => (0, 0, 0),
-        Color::DarkGrey => (80, 80, 80),
-        Color::Grey => (128, 128, 128),
-        Color::White => (255, 255, 255),
-        Color::Red => (255, 0, 0),
-        Color::DarkRed => (128, 0, 0),
-        Color::Green => (0, 255, 0),
-        Color::DarkGreen => (0, 128, 0),
-        Color::Blue => (0, 0, 255),
-        Color::DarkBlue => (0, 0, 128),
-        Color::Yellow => (255, 255, 0),
-        Color::DarkYellow => (128, 128, 0),
-        Color::Magenta => (255, 0, 255),
-        Color::DarkMagenta => (128, 0, 128),
-        Color::Cyan => (0, 255, 255),
-        Color::DarkCyan => (0, 128, 128),
-        _ => (0, 255, 0),
+/// Genau ein Zeichen, das eine Terminalzelle belegt.
+fn parse_empty_char(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) if ch.width() == Some(1) => Ok(ch),
+        (Some(_), None) => Err(format!("`{s}` belegt nicht genau eine Terminalzelle")),
+        _ => Err(format!("`{s}` ist nicht genau ein Zeichen")),
     }
 }
 
-#[derive(Parser, Debug)]
-#[command(
-    name = "matrix",
-    version,
-    about = "Retro-futuristischer Matrix-Digital-Rain in Rust"
-)]
-struct Args {
-    /// Ziel-String, in dem die fallenden Zeichen „einrasten“
-    #[arg(short, long, default_value = "Hallo Welt!")]
-    string: String,
+fn parse_color(s: &str) -> Result<Color, String> {
+    hex_to_color(s).map_err(|err| format!("ungültige Hex-Farbe `{s}`: {err}"))
+}
 
-    /// Farbset: determination, city, 2077, thermography
-    #[arg(short, long, value_enum)]
-    colorset: Option<ColorSetName>,
+fn parse_border_color(s: &str) -> Result<BorderColor, String> {
+    if s.eq_ignore_ascii_case("auto") {
+        return Ok(BorderColor::Auto);
+    }
+    parse_color(s).map(BorderColor::Fixed)
+}
 
-    /// Liste der verfügbaren Farbsets anzeigen und beenden
-    #[arg(long, conflicts_with = "colorset")]
-    list: bool,
+/// Übernimmt die Zeichen in Eingabereihenfolge ohne Duplikate.
+fn parse_charset_chars(s: &str) -> Result<Vec<char>, String> {
+    let mut chars = Vec::new();
+    for ch in s.chars().filter(|c| !c.is_control()) {
+        if !chars.contains(&ch) {
+            chars.push(ch);
+        }
+    }
+    if chars.is_empty() {
+        return Err("Zeichensatz ist leer".to_string());
+    }
+    Ok(chars)
+}
 
-    /// Hintergrund-Verschiebungsgeschwindigkeit (0-10)
-    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u8).range(0..=10))]
-    scroll_speed: u8,
+/// Liste aus `ZEICHEN=GEWICHT`, kommagetrennt.
+fn parse_charset_weights(s: &str) -> Result<Vec<(char, u32)>, String> {
+    s.split(',')
+        .map(|entry| {
+            let (glyph, weight) = entry
+                .rsplit_once('=')
+                .ok_or_else(|| format!("`{entry}`: erwartet ZEICHEN=GEWICHT"))?;
+            let mut chars = glyph.chars();
+            let (Some(ch), None) = (chars.next(), chars.next()) else {
+                return Err(format!("`{glyph}` ist nicht genau ein Zeichen"));
+            };
+            let weight = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("`{weight}` ist kein Gewicht (ganze Zahl ab 0)"))?;
+            Ok((ch, weight))
+        })
+        .collect()
+}
+
+/// Jedes Zeichen von `charset` hat Gewicht 0; nicht genannte wiegen 1, bei doppelt
+/// genannten gilt die letzte Angabe.
+fn hides_every_glyph(charset: &[char], weights: &[(char, u32)]) -> bool {
+    charset.iter().all(|ch| {
+        weights
+            .iter()
+            .rev()
+            .find(|(c, _)| c == ch)
+            .is_some_and(|&(_, weight)| weight == 0)
+    })
+}
+
+/// Liest Kommandozeile, Profil und Thema; Thema und Profil werden den echten Flags
+/// vorangestellt, sodass spätere Angaben sie überschreiben. Die Treffer gehen für
+/// `--print-config` mit zurück.
+fn parse_args() -> (Args, ArgMatches) {
+    let cli = Args::command().get_matches();
+    let path = match cli.get_one::<PathBuf>("config") {
+        Some(path) => Some(path.clone()),
+        None => Profile::default_path(),
+    };
+    let theme = cli.get_one::<Theme>("theme").copied();
+    if path.is_none() && theme.is_none() {
+        let args = Args::from_arg_matches(&cli).unwrap_or_else(|err| err.exit());
+        return (args, cli);
+    }
+
+    let on_cli = |id: &str| cli.value_source(id) == Some(ValueSource::CommandLine);
+    let profile_args = match path {
+        Some(path) => Profile::load(&path)
+            .unwrap_or_else(|err| Args::command().error(ErrorKind::Io, err).exit())
+            .to_args(on_cli),
+        None => Vec::new(),
+    };
+    let in_profile = |id: &str| {
+        let flag = format!("--{}", id.replace('_', "-"));
+        profile_args.iter().any(|arg| *arg == *flag)
+    };
+    let mut argv: Vec<OsString> = std::env::args_os().take(1).collect();
+    if let Some(theme) = theme {
+        argv.extend(theme.to_args(|id| on_cli(id) || in_profile(id)));
+    }
+    argv.extend(profile_args);
+    argv.extend(std::env::args_os().skip(1));
+    let matches = Args::command()
+        .args_override_self(true)
+        .try_get_matches_from(argv)
+        .unwrap_or_else(|err| err.exit());
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    (args, matches)
+}
+
+/// Alle Optionen mit Wert als TOML im Format der Profil-Datei. Was ein Profil setzen
+/// kann, steht als Schlüssel darin, sodass sich die Ausgabe mit `--config` wieder laden
+/// lässt; alle übrigen Optionen folgen auskommentiert.
+fn resolved_config(args: &Args, matches: &ArgMatches) -> String {
+    let profile = resolved_profile(args);
+    let mut out = toml::to_string(&profile).unwrap_or_default();
+    let in_profile = toml::Table::try_from(&profile).unwrap_or_default();
+    for (key, value) in resolved_options(matches) {
+        if !in_profile.contains_key(&key) {
+            out.push_str(&format!("# {key} = {value}\n"));
+        }
+    }
+    out
+}
+
+/// Wirksame Werte der Optionen, die ein Profil kennt. Mehrere `--string` oder
+/// `--string-file` lassen sich dort nicht abbilden und bleiben außen vor.
+fn resolved_profile(args: &Args) -> Profile {
+    let name =
+        |value: Option<clap::builder::PossibleValue>| value.map(|v| v.get_name().to_string());
+    Profile {
+        string: match args.string.as_slice() {
+            [string] if args.string_file.is_none() => Some(string.clone()),
+            _ => None,
+        },
+        colorset: args.colorset.map(|set| set.name().to_string()),
+        colors: args.colors.clone(),
+        fps: Some(args.fps),
+        trail: Some(args.trail),
+        charset: name(args.charset.to_possible_value()),
+        density: Some(args.density),
+        direction: name(args.direction.to_possible_value()),
+    }
 }
 
-#[derive(Clone)]
-struct Column {
-    x: u16,
-    head_y: i16,
-    speed: u64,
-    phase: usize,
+/// Alle Optionen mit Wert als TOML-Tabelle; Schlüssel wie die langen Flags.
+/// Mehrfach angegebene Optionen werden zu Arrays.
+fn resolved_options(matches: &ArgMatches) -> toml::Table {
+    let mut table = toml::Table::new();
+    for arg in Args::command().get_arguments() {
+        let id = arg.get_id().as_str();
+        // Die Profil-Datei selbst gehört nicht in die Ausgabe
+        if matches!(id, "print_config" | "config") {
+            continue;
+        }
+        let Some(raw) = matches.get_raw(id) else {
+            continue;
+        };
+        let mut values: Vec<toml::Value> = raw.map(|v| toml_value(&v.to_string_lossy())).collect();
+        let value = match values.len() {
+            1 => values.remove(0),
+            _ => toml::Value::Array(values),
+        };
+        table.insert(id.replace('_', "-"), value);
+    }
+    table
+}
+
+/// Rohwert der Kommandozeile als TOML-Wert: Wahrheitswerte und Zahlen typisiert,
+/// alles andere als Zeichenkette.
+fn toml_value(raw: &str) -> toml::Value {
+    if let Ok(b) = raw.parse::<bool>() {
+        toml::Value::Boolean(b)
+    } else if let Ok(n) = raw.parse::<i64>() {
+        toml::Value::Integer(n)
+    } else if let Ok(x) = raw.parse::<f64>()
+        && x.is_finite()
+    {
+        toml::Value::Float(x)
+    } else {
+        toml::Value::String(raw.to_string())
+    }
 }
 
 fn main() -> std::io::Result<()> {
-    let args = Args::parse();
+    let (args, matches) = parse_args();
 
     if args.list {
         println!("Verfügbare Farbsets:");
-        for variant in ColorSetName::value_variants() {
-            if let Some(value) = variant.to_possible_value() {
-                println!("  {}", value.get_name());
-            }
+        for (name, _) in builtin_sets() {
+            println!("  {name}");
         }
         return Ok(());
     }
 
-    let target = args.string;
-    let colorset = ColorSet::from_name(args.colorset.unwrap_or(ColorSetName::Determination));
-    let scroll_speed = args.scroll_speed;
+    if args.speed_min > args.speed_max {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "--speed-min ({}) darf nicht größer als --speed-max ({}) sein",
+                    args.speed_min, args.speed_max
+                ),
+            )
+            .exit();
+    }
+    let filter = CharFilter {
+        uppercase: !args.no_uppercase,
+        lowercase: !args.no_lowercase,
+        digits: !args.no_digits,
+    };
+    let charset = args.charset.chars_filtered(filter);
+    if charset.is_empty() {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--no-uppercase, --no-lowercase und --no-digits lassen keine Zeichen im Zeichensatz übrig",
+            )
+            .exit();
+    }
+    if let Some(weights) = &args.charset_weights
+        && hides_every_glyph(args.charset_chars.as_deref().unwrap_or(&charset), weights)
+    {
+        Args::command()
+            .error(
+                ErrorKind::ValueValidation,
+                "--charset-weights gibt jedem Zeichen des Zeichensatzes Gewicht 0",
+            )
+            .exit();
+    }
+    let trail_len = args.trail_max.unwrap_or(args.trail);
+    if let Some(trail_min) = args.trail_min
+        && trail_min > trail_len
+    {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "--trail-min ({trail_min}) darf nicht größer als --trail-max bzw. --trail ({trail_len}) sein"
+                ),
+            )
+            .exit();
+    }
+    if args.invert && args.style == Style::Simple {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--invert braucht einen Ziel-Text und passt nicht zu --style simple",
+            )
+            .exit();
+    }
+    if args.decrypt.is_some() && args.style == Style::Simple {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--decrypt braucht einen Ziel-Text und passt nicht zu --style simple",
+            )
+            .exit();
+    }
+    if args.marquee.is_some() && args.style == Style::Simple {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--marquee braucht einen Ziel-Text und passt nicht zu --style simple",
+            )
+            .exit();
+    }
+
+    let colorset = match (&args.colors, &args.palette_file) {
+        (Some(hexes), _) => {
+            let hexes: Vec<&str> = hexes.iter().map(String::as_str).collect();
+            ColorSet::from_hex(&hexes)
+        }
+        (None, Some(path)) => match GimpPalette::load(path) {
+            Ok(palette) => {
+                for line in &palette.skipped {
+                    eprintln!(
+                        "Warnung: {}:{line}: keine Farbe (R G B Name), übersprungen",
+                        path.display()
+                    );
+                }
+                palette.color_set()
+            }
+            Err(err) => Args::command()
+                .error(
+                    ErrorKind::InvalidValue,
+                    format!("{}: {err}", path.display()),
+                )
+                .exit(),
+        },
+        (None, None) => ColorSet::from_name(args.colorset.unwrap_or(ColorSetName::Determination)),
+    }
+    .with_blend(args.blend)
+    .with_background(args.background.unwrap_or(Color::Black))
+    .with_brightness(args.brightness.unwrap_or(0.0))
+    .with_contrast(args.contrast.unwrap_or(1.0));
+    let figlet = match &args.figlet {
+        Some(path) => match FigletFont::load(path) {
+            Ok(font) => Some(font),
+            Err(err) => Args::command()
+                .error(
+                    ErrorKind::InvalidValue,
+                    format!("{}: {err}", path.display()),
+                )
+                .exit(),
+        },
+        None => None,
+    };
+    // Vor dem Raw-Modus lesen; die Tastatur kommt danach über das Terminal, nicht über stdin
+    let mut texts: Vec<String> = args
+        .string
+        .iter()
+        .map(|string| match string.as_str() {
+            "-" => {
+                let mut text = String::new();
+                let read = std::io::stdin().read_to_string(&mut text).map(|_| text);
+                read_text(Path::new("stdin"), read)
+            }
+            _ => string.clone(),
+        })
+        .collect();
+    if let Some(path) = &args.string_file {
+        texts = vec![read_text(path, std::fs::read_to_string(path))];
+    }
+    // Der erste String ist der Haupt-Text, weitere werden eigene Blöcke
+    let align = |i: usize| args.text_align.get(i).copied().unwrap_or_default();
+    let halign = |i: usize| args.text_halign.get(i).copied().unwrap_or_default();
+    let extra_texts = texts
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, text)| TextBlock {
+            text: text.clone(),
+            align: align(i),
+            halign: halign(i),
+        })
+        .collect();
+    let text = texts.into_iter().next().unwrap_or_default();
+    let config = RainConfig {
+        text,
+        font: args.font,
+        letter_spacing: args.letter_spacing,
+        line_spacing: args.line_spacing,
+        figlet,
+        colorset,
+        trail_gradient: args.head_color.zip(args.tail_color),
+        reverse_gradient: args.reverse_gradient,
+        scroll_speed: args.scroll_speed,
+        marquee: args.marquee,
+        trail_len,
+        trail_min: args.trail_min,
+        direction: args.direction,
+        seed: args.seed,
+        density: args.density,
+        continuous: args.continuous,
+        speed_min: args.speed_min,
+        speed_max: args.speed_max,
+        jitter: args.jitter,
+        ascii_shading: args.ascii_shading,
+        style: args.style,
+        glow: args.glow.unwrap_or(0.0),
+        flicker: args.flicker,
+        reveal: args.reveal,
+        text_align: align(0),
+        text_halign: halign(0),
+        extra_texts,
+        rainbow: args.rainbow,
+        column_hue: args.per_column_hue.unwrap_or(0.0),
+        gradient: args.gradient,
+        invert: args.invert,
+        depth_shading: args.depth_shading,
+        bold_head: !args.no_bold_head,
+        fade_in: args.fade_in.unwrap_or_default(),
+        shimmer: args.shimmer,
+        gravity: args.gravity,
+        mirror: args.mirror,
+        ghost_alpha: args.ghost_alpha,
+        ambient: args.ambient,
+        wake: args.wake,
+        reveal_wpm: args.reveal_wpm,
+        cursor: args.cursor,
+        decrypt: args.decrypt,
+        celebrate: args.celebrate,
+        intro_delay: args.intro_delay.unwrap_or_default(),
+        border: args.border,
+        border_color: args.border_color.unwrap_or_default(),
+        charset: args.charset_chars.clone().unwrap_or(charset),
+        charset_weights: args.charset_weights.clone().unwrap_or_default(),
+        on_frame: None,
+    };
+
+    // Spaltengeschwindigkeiten bleiben in Millisekunden, die Bildrate ändert nur die Abtastung
+    let frame_delay = Duration::from_millis(1000 / args.fps as u64);
 
-    let mut stdout = stdout();
-    terminal::enable_raw_mode()?;
-    stdout.execute(terminal::EnterAlternateScreen)?;
-    stdout.execute(cursor::Hide)?;
+    let exit_after = args
+        .exit_on_complete
+        .then(|| Duration::from_millis(args.hold));
+    let duration = args.duration.filter(|d| !d.is_zero());
 
-    let (width, height) = terminal::size()?;
-    let height_i16 = height as i16;
+    // Nach allen Prüfungen, aber vor jeder Ausgabe- oder Terminal-Einrichtung
+    if args.print_config {
+        print!("{}", resolved_config(&args, &matches));
+        return Ok(());
+    }
 
-    // Ziel-String in integrierter 3x5-Schrift
-    let figlet_lines = render_3x5(&target);
-    let mut target_lines: Vec<Vec<char>> =
-        figlet_lines.iter().map(|l| l.chars().collect()).collect();
-    let target_height = target_lines.len().max(1) as u16;
-    let target_width = target_lines.iter().map(|l| l.len()).max().unwrap_or(0) as u16;
-    for line in target_lines.iter_mut() {
-        if line.len() < target_width as usize {
-            line.extend(std::iter::repeat_n(' ', target_width as usize - line.len()));
+    if args.output.is_some() || args.screenshot.is_some() {
+        let (width, height) = grid_size(args.width, args.height, None);
+        let mut state = RainState::with_config(config, width, height);
+        check_overlap(&state);
+        let mut out: Box<dyn Write> = match &args.output {
+            Some(path) if path.as_os_str() == "-" => Box::new(stdout().lock()),
+            Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+            None => Box::new(std::io::sink()),
+        };
+        let limit = duration.map(|d| frames_within(d, frame_delay));
+        let frames = match args.frames {
+            Some(frames) => limit.map_or(frames, |limit| frames.min(limit)),
+            None => limit.unwrap_or(SCREENSHOT_SECONDS * args.fps as u32),
+        };
+        let mut renderer = TextRenderer::new(&mut out);
+        if let Some(ch) = args.empty_char {
+            renderer = renderer.with_empty_char(ch);
+        }
+        render_frames(&mut renderer, &mut state, frames, frame_delay, exit_after)?;
+        if let Some(path) = &args.screenshot {
+            let background = args.background.unwrap_or(Color::Black);
+            write_png(
+                state.grid(),
+                background,
+                BufWriter::new(File::create(path)?),
+            )?;
         }
+        return Ok(());
     }
 
-    // Ziel-Block zentrieren
-    let start_x = if target_width < width {
-        (width - target_width) / 2
-    } else {
-        0
-    };
-    let max_y = height.saturating_sub(1);
-    let target_y = (height.saturating_sub(target_height) / 2).min(max_y);
-    let border_y0 = target_y.saturating_sub(1);
-    let border_y1 = (target_y + target_height).min(max_y);
-    let max_x = width.saturating_sub(1);
-    let border_x0 = start_x.saturating_sub(1);
-    let border_x1 = (start_x + target_width).min(max_x);
-
-    // Für jedes Zeichen im Ziel-String merken wir, ob es schon „eingeloggt“ ist
-    let mut locked_chars: Vec<Vec<Option<char>>> =
-        vec![vec![None; target_width as usize]; target_height as usize];
-
-    // Zeichensatz für Regen
-    let charset: Vec<char> = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"
-        .chars()
-        .collect();
-    let mut frame: usize = 0;
-    let mut bg_shift: u16 = 0;
-    let mut bg_tick: u16 = 0;
-    let scroll_interval: u16 = if scroll_speed == 0 {
-        u16::MAX
-    } else {
-        11 - scroll_speed as u16
+    let color = !args.no_color && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty());
+    let render = RenderOptions {
+        color,
+        depth: args.color_depth.unwrap_or_else(ColorDepth::detect),
+        background: args.background.filter(|_| color),
+        empty_char: args.empty_char,
+        empty_color: args.empty_color,
     };
 
-    // Spalten initialisieren
-    let mut rng = rand::thread_rng();
-    let mut columns: Vec<Column> = (0..width)
-        .map(|x| Column {
-            x,
-            head_y: rng.gen_range(-20..0),
-            speed: rng.gen_range(40..120), // ms pro Schritt
-            phase: rng.gen_range(0..charset.len()),
-        })
-        .collect();
+    let shutdown = Arc::new(AtomicBool::new(false));
+    register_shutdown(&shutdown)?;
+
+    let detected = terminal::size().ok();
+    let (width, height) = grid_size(args.width, args.height, detected);
+    // Meldet das Terminal 0 Spalten oder Zeilen (manche CI-/tmux-Zustände), wird bis zum
+    // nächsten Resize weder gerechnet noch gezeichnet
+    let mut no_room = detected.is_some_and(|size| no_room_in(args.width, args.height, size));
+    // Vor dem Raw-Modus, damit eine Fehlermeldung im normalen Terminal landet
+    let mut state = RainState::with_config(config, width, height);
+    check_overlap(&state);
 
-    // Zeittracking pro Spalte
-    let mut last_update: Vec<Instant> = columns.iter().map(|_| Instant::now()).collect();
+    // Mit --record gehen alle Bytes zusätzlich in die Aufnahme, ein Ereignis pro Frame
+    let out = match &args.record {
+        Some(path) => {
+            let file = BufWriter::new(File::create(path)?);
+            Output::Record(CastRecorder::new(stdout(), file, width, height)?)
+        }
+        None => Output::Terminal(stdout()),
+    };
+    // Ein ganzer Frame passt in den Puffer und geht mit einem `flush` hinaus, statt
+    // in vielen kleinen Schreibaufrufen über den Zeilenpuffer von `Stdout`
+    let stdout = BufWriter::with_capacity(frame_capacity(width, height), out);
+    let mut renderer = CrosstermRenderer::new(stdout, render);
+    let guard = TerminalGuard::enter(renderer.get_mut(), args.mouse)?;
 
-    // Hintergrund schwarz
-    stdout
-        .execute(terminal::Clear(ClearType::All))?
-        .execute(cursor::MoveTo(0, 0))?;
+    renderer.clear()?;
+    renderer.get_mut().execute(cursor::MoveTo(0, 0))?;
 
-    // Hauptloop
+    // Hauptloop; `prev` hält das zuletzt ausgegebene Raster für differenzielle Updates
+    let mut prev = Grid::new(width, height);
+    // Mit Füllzeichen steht nach dem Leeren nicht das leere Raster auf dem Bildschirm;
+    // dann den ersten Frame vollständig zeichnen
+    let mut redraw = args.empty_char.is_some();
+    let started = Instant::now();
+    let mut last_frame = started;
+    let mut paused = false;
+    let mut completed: Option<Instant> = None;
+    // Aktives eingebautes Farbset für `c`/`C`; bei --colors und --palette-file noch keines
+    let builtin = ColorSetName::value_variants();
+    let mut colorset_index = if args.colors.is_some() || args.palette_file.is_some() {
+        None
+    } else {
+        builtin
+            .iter()
+            .position(|n| Some(*n) == args.colorset)
+            .or(Some(0))
+    };
+    let mut flash: Option<(String, Instant)> = None;
+    let mut overlay = prev.clone();
+    // Statuszeile oben links für `f`/`F`; gemessen wird auch, solange sie aus ist
+    let mut show_stats = false;
+    let mut stats = FrameStats::new(started);
+    let mut stats_width = 0;
     'outer: loop {
-        // Eingabe prüfen (q oder ESC beendet)
+        let frame_start = Instant::now();
+        // Signal von außen (kill, Fenstermanager) fällt zum Aufräumen durch
+        if shutdown.load(Ordering::Relaxed) {
+            break;
+        }
+        if duration.is_some_and(|d| started.elapsed() >= d) {
+            break;
+        }
+
+        // Eingabe prüfen (q oder ESC beendet, Leertaste pausiert, `.` macht einen Schritt,
+        // `[`/`]` ändern die Verschiebungsgeschwindigkeit, `f` schaltet die Statuszeile)
+        let mut single_step = false;
         while event::poll(Duration::from_millis(0))? {
+            let event = event::read()?;
+            // Neue Größe: Simulation anpassen und alles neu ausgeben
+            if let Event::Resize(w, h) = event {
+                no_room = no_room_in(args.width, args.height, (w, h));
+                if no_room {
+                    continue;
+                }
+                let (width, height) = grid_size(args.width, args.height, Some((w, h)));
+                state.resize(width, height);
+                prev = Grid::new(width, height);
+                redraw = args.empty_char.is_some();
+                renderer.clear()?;
+                continue;
+            }
+            // Linksklick (mit --mouse): Tropfen um die Klickstelle neu starten
+            if let Some((column, row)) = left_click(&event) {
+                state.burst(column, row);
+                continue;
+            }
             if let Event::Key(KeyEvent {
                 code, modifiers, ..
-            }) = event::read()?
+            }) = event
             {
                 match code {
                     KeyCode::Char('q') | KeyCode::Esc => break 'outer,
                     KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => break 'outer,
+                    KeyCode::Char(' ') => paused = !paused,
+                    KeyCode::Char('.') if paused => single_step = true,
+                    KeyCode::Char('f' | 'F') => show_stats = !show_stats,
+                    KeyCode::Char(key @ ('c' | 'C')) => {
+                        let next = next_colorset(colorset_index, key == 'c', builtin.len());
+                        colorset_index = Some(next);
+                        let name = builtin[next];
+                        state.set_colorset(
+                            ColorSet::from_name(name)
+                                .with_blend(args.blend)
+                                .with_background(args.background.unwrap_or(Color::Black))
+                                .with_brightness(args.brightness.unwrap_or(0.0))
+                                .with_contrast(args.contrast.unwrap_or(1.0)),
+                        );
+                        flash = Some((name.name().to_string(), Instant::now()));
+                    }
+                    KeyCode::Char(key @ ('[' | ']')) => {
+                        let speed = step_scroll_speed(state.config().scroll_speed, key == ']');
+                        state.set_scroll_speed(speed);
+                        flash = Some((format!("scroll {speed}"), Instant::now()));
+                    }
                     _ => {}
                 }
             }
         }
 
-        // Frame-Tick für durchlaufende Zeichenrotation
-        frame = frame.wrapping_add(1);
-        bg_tick = bg_tick.wrapping_add(1);
-        if bg_tick.is_multiple_of(scroll_interval) {
-            bg_shift = (bg_shift + 1) % width.max(1);
+        // Pausiert ruht die Simulation; die Uhr läuft mit, damit es danach nicht springt
+        let now = Instant::now();
+        let dt = now - last_frame;
+        last_frame = now;
+        if no_room {
+            thread::sleep(frame_delay);
+            continue;
         }
-
-        // Regen aktualisieren
-        for (i, col) in columns.iter_mut().enumerate() {
-            if last_update[i].elapsed() < Duration::from_millis(col.speed) {
-                continue;
+        if let Some(dt) = sim_step(paused, single_step, dt, frame_delay) {
+            state.step(dt);
+        }
+        let grid = state.grid();
+        // Name des Farbsets kurz oben rechts und die Statuszeile oben links einblenden,
+        // ohne das Simulationsraster zu verändern
+        let label = flash
+            .as_ref()
+            .filter(|(_, since)| since.elapsed() < FLASH_DURATION)
+            .map(|(label, _)| label);
+        let grid = if label.is_some() || show_stats {
+            overlay.clone_from(grid);
+            if let Some(label) = label {
+                draw_label(&mut overlay, label);
+            }
+            if show_stats {
+                let line = stats.line(state.active_columns());
+                draw_stats(&mut overlay, &line, |x, y| state.in_text_area(x, y));
+                // Samt der Leerzeichen links und rechts
+                stats_width = stats_width.max(line.chars().count() as u16 + 2);
             }
-            last_update[i] = Instant::now();
-            col.phase = (col.phase + 1) % charset.len();
+            &overlay
+        } else {
+            grid
+        };
+        let drawn = renderer.draw_grid(grid, (!redraw).then_some(&prev))?;
+        // Zellen der Statuszeile (auch beim Ausblenden) zählen nicht mit
+        let own = overlay_cells(grid, (!redraw).then_some(&prev), stats_width);
+        if !show_stats {
+            stats_width = 0;
+        }
+        stats.record(now, drawn, own);
+        redraw = false;
+        prev.clone_from(grid);
 
-            // Kopf eine Zeile nach unten
-            col.head_y += 1;
+        if let Some(hold) = exit_after
+            && state.is_complete()
+            && !state.is_celebrating()
+            && completed.get_or_insert(now).elapsed() >= hold
+        {
+            break;
+        }
+        // Nur den Rest des Frame-Budgets schlafen, damit die Bildrate auch bei
+        // langsamer Ausgabe hält
+        thread::sleep(frame_sleep(frame_delay, frame_start.elapsed()));
+    }
 
-            // Trail-Länge
-            let trail_len = 10;
-            for offset in 0..=trail_len {
-                let y = col.head_y - offset;
-                if y < 0 || y >= height_i16 {
-                    continue;
-                }
-                let y_u16 = y as u16;
-                let draw_x = (col.x + bg_shift) % width.max(1);
-                let in_target_area = draw_x >= border_x0
-                    && draw_x <= border_x1
-                    && y_u16 >= border_y0
-                    && y_u16 <= border_y1;
-                if in_target_area {
-                    continue;
-                }
+    // Aufräumen; eine laufende Aufnahme wird abgeschlossen und meldet Schreibfehler
+    let out = renderer
+        .into_inner()
+        .into_inner()
+        .map_err(std::io::IntoInnerError::into_error)?;
+    out.finish()?;
+    drop(guard);
 
-                // Helligkeit entlang des Trails (0 = Kopf, 1 = Ende)
-                let t = offset as f32 / trail_len as f32;
-                let color = colorset.gradient_color(1.0 - t);
-
-                // Kopf heller/fetter
-                let ch =
-                    charset[(frame + col.phase + col.x as usize + offset as usize) % charset.len()];
-                let styled = if offset == 0 {
-                    ch.with(color).bold()
-                } else {
-                    ch.with(color)
-                };
-
-                stdout
-                    .queue(cursor::MoveTo(draw_x, y_u16))?
-                    .queue(PrintStyledContent(styled))?;
-            }
+    // Nach dem Verlassen des alternativen Bildschirms bleibt der Text im Scrollback
+    if args.keep_text {
+        let mut out = std::io::stdout().lock();
+        out.write_all(state.revealed_text().as_bytes())?;
+        out.flush()?;
+    }
+    Ok(())
+}
 
-            // Wenn Kopf unterhalb der Zielzeile ist, prüfen, ob wir ein Zeichen „einloggen“
-            let col_x = (col.x + bg_shift) % width.max(1);
-            if col_x >= start_x && col_x < start_x + target_width {
-                let row = col.head_y as i32 - target_y as i32;
-                if row >= 0 && (row as u16) < target_height {
-                    let row_idx = row as usize;
-                    let col_idx = (col_x - start_x) as usize;
-                    if locked_chars[row_idx][col_idx].is_none() {
-                        let target_ch = target_lines
-                            .get(row_idx)
-                            .and_then(|line| line.get(col_idx))
-                            .copied()
-                            .unwrap_or(' ');
-                        if target_ch != ' ' {
-                            locked_chars[row_idx][col_idx] = Some(target_ch);
-                        }
-                    }
-                }
-            }
+/// Index des eingebauten Farbsets nach `current` (`c`) bzw. davor (`C`). Ohne aktives
+/// eingebautes Set (`--colors`, `--palette-file`) beginnt die Runde beim ersten bzw. letzten.
+fn next_colorset(current: Option<usize>, forward: bool, len: usize) -> usize {
+    match (current, forward) {
+        (Some(i), true) => (i + 1) % len,
+        (Some(i), false) => (i + len - 1) % len,
+        (None, true) => 0,
+        (None, false) => len - 1,
+    }
+}
 
-            // Wenn Kopf unten raus ist, Spalte neu starten
-            if col.head_y >= height_i16 + trail_len {
-                col.head_y = rng.gen_range(-20..0);
-                col.speed = rng.gen_range(40..120);
-                col.phase = rng.gen_range(0..charset.len());
-            }
+/// Simulationszeit vor einem --screenshot ohne --frames
+const SCREENSHOT_SECONDS: u32 = 5;
+
+/// Bildlaufgeschwindigkeit nach `]` (schneller) bzw. `[` (langsamer), begrenzt auf 0–10.
+fn step_scroll_speed(speed: u8, faster: bool) -> u8 {
+    if faster {
+        (speed + 1).min(10)
+    } else {
+        speed.saturating_sub(1)
+    }
+}
+
+/// Rasterposition eines Linksklicks.
+fn left_click(event: &Event) -> Option<(u16, u16)> {
+    match event {
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            ..
+        }) => Some((*column, *row)),
+        _ => None,
+    }
+}
+
+/// Frames im Abstand `frame_delay`, bis die Simulationszeit `duration` erreicht ist.
+fn frames_within(duration: Duration, frame_delay: Duration) -> u32 {
+    let frames = duration.as_nanos().div_ceil(frame_delay.as_nanos().max(1));
+    u32::try_from(frames).unwrap_or(u32::MAX)
+}
+
+/// Schlafdauer nach einem Frame, der `elapsed` gedauert hat: was vom Budget `frame_delay`
+/// übrig ist, bei zu langsamen Frames nichts.
+fn frame_sleep(frame_delay: Duration, elapsed: Duration) -> Duration {
+    frame_delay.saturating_sub(elapsed)
+}
+
+/// Wie lange der Name eines gewechselten Farbsets eingeblendet bleibt
+const FLASH_DURATION: Duration = Duration::from_millis(1500);
+
+/// Schreibt ` label ` rechtsbündig in die oberste Zeile.
+fn draw_label(grid: &mut Grid, label: &str) {
+    let text = format!(" {label} ");
+    let x0 = grid.width().saturating_sub(text.chars().count() as u16);
+    for (x, ch) in (x0..).zip(text.chars()) {
+        let cell = Cell {
+            ch,
+            fg: Color::White,
+            bold: true,
+            dim: false,
+            head: false,
+        };
+        grid.set(x, 0, cell);
+    }
+}
+
+/// Länge des Messfensters für die Bildrate der Statuszeile
+const STATS_WINDOW: Duration = Duration::from_secs(1);
+
+/// Kennzahlen für die mit `f`/`F` eingeblendete Statuszeile.
+struct FrameStats {
+    /// Beginn des laufenden Messfensters und seither ausgegebene Frames
+    window_start: Instant,
+    window_frames: u32,
+    /// Bildrate über das letzte volle Messfenster
+    fps: f32,
+    /// Im letzten Frame gezeichnete Zellen ohne die der Statuszeile
+    drawn: usize,
+}
+
+impl FrameStats {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            window_frames: 0,
+            fps: 0.0,
+            drawn: 0,
         }
+    }
 
-        // Rahmen zeichnen
-        if width > 0 && height > 0 {
-            let border_style = '+'.with(Color::DarkGrey);
-            let horiz_style = '-'.with(Color::DarkGrey);
-            let vert_style = '|'.with(Color::DarkGrey);
-
-            if border_x0 <= border_x1 {
-                for x in border_x0..=border_x1 {
-                    let ch = if (x == border_x0 || x == border_x1)
-                        && (border_y0 == border_y1
-                            || border_y0 == target_y
-                            || border_y1 == target_y)
-                    {
-                        border_style
-                    } else {
-                        horiz_style
-                    };
-                    stdout
-                        .queue(cursor::MoveTo(x, border_y0))?
-                        .queue(PrintStyledContent(ch))?;
-                    if border_y1 != border_y0 {
-                        stdout
-                            .queue(cursor::MoveTo(x, border_y1))?
-                            .queue(PrintStyledContent(ch))?;
-                    }
-                }
-            }
-            if border_y0 < border_y1.saturating_sub(1) && border_x0 <= border_x1 {
-                for y in (border_y0 + 1)..=border_y1.saturating_sub(1) {
-                    stdout
-                        .queue(cursor::MoveTo(border_x0, y))?
-                        .queue(PrintStyledContent(vert_style))?;
-                    if border_x1 != border_x0 {
-                        stdout
-                            .queue(cursor::MoveTo(border_x1, y))?
-                            .queue(PrintStyledContent(vert_style))?;
-                    }
-                }
-            }
+    /// Zählt einen ausgegebenen Frame mit `drawn` Zellen, davon `own` für die Statuszeile.
+    fn record(&mut self, now: Instant, drawn: usize, own: usize) {
+        self.drawn = drawn.saturating_sub(own);
+        self.window_frames += 1;
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed >= STATS_WINDOW {
+            self.fps = self.window_frames as f32 / elapsed.as_secs_f32();
+            self.window_start = now;
+            self.window_frames = 0;
         }
+    }
 
-        // Ziel-String zeichnen (eingeloggte Zeichen hervorgehoben)
-        for (row, line) in target_lines.iter().enumerate() {
-            let y = target_y + row as u16;
-            for (col, ch) in line.iter().enumerate() {
-                if *ch == ' ' {
-                    continue;
-                }
-                let x = start_x + col as u16;
-                let locked = locked_chars[row][col].is_some();
-                let base_color = Color::White;
-                let styled = if locked {
-                    ch.with(base_color).bold()
-                } else {
-                    ch.with(Color::DarkGrey)
-                };
-                stdout
-                    .queue(cursor::MoveTo(x, y))?
-                    .queue(PrintStyledContent(styled))?;
+    fn line(&self, columns: usize) -> String {
+        format!("{:.0} fps  {columns} col  {} cells", self.fps, self.drawn)
+    }
+}
+
+/// Schreibt ` line ` linksbündig in die oberste Zeile; Zellen, für die `reserved` gilt
+/// (Ziel-Text und Rahmen), bleiben frei.
+fn draw_stats(grid: &mut Grid, line: &str, reserved: impl Fn(u16, u16) -> bool) {
+    for (x, ch) in (0..grid.width()).zip(format!(" {line} ").chars()) {
+        if reserved(x, 0) {
+            continue;
+        }
+        let cell = Cell {
+            ch,
+            fg: Color::White,
+            bold: true,
+            dim: false,
+            head: false,
+        };
+        grid.set(x, 0, cell);
+    }
+}
+
+/// Änderungen in den ersten `width` Zellen der obersten Zeile, die `draw_grid` mit
+/// `prev` ausgibt; ohne `prev` wird die ganze Breite gezeichnet.
+fn overlay_cells(grid: &Grid, prev: Option<&Grid>, width: u16) -> usize {
+    match prev {
+        Some(prev) => grid
+            .changes(prev)
+            .filter(|&(x, y, _)| y == 0 && x < width)
+            .count(),
+        None => width.min(grid.width()) as usize,
+    }
+}
+
+/// Bricht ab, wenn sich die Blöcke mehrerer --string samt Rahmen überlappen.
+fn check_overlap(state: &RainState) {
+    if state.targets_overlap() {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "die Blöcke der --string-Angaben überlappen sich; --text-align/--text-halign anpassen",
+            )
+            .exit();
+    }
+}
+
+/// Ziel-Text aus Datei oder stdin; abschließende Zeilenumbrüche fallen weg.
+fn read_text(source: &Path, read: std::io::Result<String>) -> String {
+    match read {
+        Ok(text) => text.trim_end_matches(['\n', '\r']).to_string(),
+        Err(err) => Args::command()
+            .error(ErrorKind::Io, format!("{}: {err}", source.display()))
+            .exit(),
+    }
+}
+
+/// Das Terminal hat in einer Richtung ohne Überschreibung keine einzige Zelle.
+fn no_room_in(width: Option<u16>, height: Option<u16>, (w, h): (u16, u16)) -> bool {
+    (width.is_none() && w == 0) || (height.is_none() && h == 0)
+}
+
+/// Rastergröße: Überschreibungen vor erkannter Größe, 80x24 wenn beides fehlt.
+/// Ohne TTY (Pipe, CI) schlägt die Erkennung fehl oder liefert 0.
+fn grid_size(width: Option<u16>, height: Option<u16>, detected: Option<(u16, u16)>) -> (u16, u16) {
+    let (w, h) = detected.unwrap_or((0, 0));
+    let width = width.unwrap_or(if w > 0 { w } else { 80 });
+    let height = height.unwrap_or(if h > 0 { h } else { 24 });
+    (width, height)
+}
+
+/// Zeitschritt der Simulation in diesem Frame: `dt` im Lauf, pausiert keiner,
+/// beim Einzelschritt (`.`) genau ein Frame.
+fn sim_step(
+    paused: bool,
+    single_step: bool,
+    dt: Duration,
+    frame_delay: Duration,
+) -> Option<Duration> {
+    match (paused, single_step) {
+        (false, _) => Some(dt),
+        (true, true) => Some(frame_delay),
+        (true, false) => None,
+    }
+}
+
+/// Rechnet `frames` Schritte ohne Terminal und zeichnet jeden Frame vollständig.
+/// Mit `exit_after` endet die Ausgabe vorzeitig, sobald der Ziel-Text seit so viel
+/// Simulationszeit vollständig ist.
+fn render_frames(
+    renderer: &mut impl Renderer,
+    state: &mut RainState,
+    frames: u32,
+    dt: Duration,
+    exit_after: Option<Duration>,
+) -> std::io::Result<()> {
+    let mut complete_for = Duration::ZERO;
+    for _ in 0..frames {
+        renderer.draw_grid(state.step(dt), None)?;
+        if let Some(hold) = exit_after
+            && state.is_complete()
+            && !state.is_celebrating()
+        {
+            if complete_for >= hold {
+                break;
             }
+            complete_for += dt;
         }
+    }
+    Ok(())
+}
 
-        stdout.flush()?;
-        thread::sleep(Duration::from_millis(16)); // ~60 FPS
+/// Setzt `flag` bei SIGINT, SIGTERM und SIGHUP. Im Raw-Mode kommt Strg+C als
+/// Tastenereignis an, SIGINT also nur per `kill -INT`.
+#[cfg(unix)]
+fn register_shutdown(flag: &Arc<AtomicBool>) -> std::io::Result<()> {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+    for signal in [SIGINT, SIGTERM, SIGHUP] {
+        signal_hook::flag::register(signal, Arc::clone(flag))?;
     }
+    Ok(())
+}
 
-    // Aufräumen
-    stdout.execute(cursor::Show)?;
-    stdout.execute(terminal::LeaveAlternateScreen)?;
-    terminal::disable_raw_mode()?;
+/// Unter Windows gibt es keine Unix-Signale; beendet wird nur per Tastatur.
+#[cfg(not(unix))]
+fn register_shutdown(_flag: &Arc<AtomicBool>) -> std::io::Result<()> {
     Ok(())
 }
 
-fn render_3x5(input: &str) -> Vec<String> {
-    let mut rows = vec![
-        String::new(),
-        String::new(),
-        String::new(),
-        String::new(),
-        String::new(),
-    ];
-
-    for ch in input.chars() {
-        let glyph = glyph_3x5(ch);
-        for (row, pattern) in rows.iter_mut().zip(glyph.iter()) {
-            row.push_str(pattern);
-            row.push(' '); // Abstand zwischen Zeichen
-        }
-    }
-
-    while rows.last().is_some_and(|r| r.is_empty()) {
-        rows.pop();
-    }
-
-    rows
-}
-
-fn glyph_3x5(ch: char) -> [&'static str; 5] {
-    match ch.to_ascii_uppercase() {
-        'A' => ["###", "# #", "###", "# #", "# #"],
-        'B' => ["## ", "# #", "## ", "# #", "## "],
-        'C' => ["###", "#  ", "#  ", "#  ", "###"],
-        'D' => ["## ", "# #", "# #", "# #", "## "],
-        'E' => ["###", "#  ", "###", "#  ", "###"],
-        'F' => ["###", "#  ", "###", "#  ", "#  "],
-        'G' => ["###", "#  ", "# #", "# #", "###"],
-        'H' => ["# #", "# #", "###", "# #", "# #"],
-        'I' => ["###", " # ", " # ", " # ", "###"],
-        'J' => ["###", "  #", "  #", "# #", "###"],
-        'K' => ["# #", "## ", "#  ", "## ", "# #"],
-        'L' => ["#  ", "#  ", "#  ", "#  ", "###"],
-        'M' => ["# #", "###", "###", "# #", "# #"],
-        'N' => ["# #", "###", "###", "###", "# #"],
-        'O' => ["###", "# #", "# #", "# #", "###"],
-        'P' => ["###", "# #", "###", "#  ", "#  "],
-        'Q' => ["###", "# #", "# #", "###", "  #"],
-        'R' => ["###", "# #", "###", "## ", "# #"],
-        'S' => ["###", "#  ", "###", "  #", "###"],
-        'T' => ["###", " # ", " # ", " # ", " # "],
-        'U' => ["# #", "# #", "# #", "# #", "###"],
-        'V' => ["# #", "# #", "# #", "# #", " # "],
-        'W' => ["# #", "# #", "###", "###", "# #"],
-        'X' => ["# #", "# #", " # ", "# #", "# #"],
-        'Y' => ["# #", "# #", " # ", " # ", " # "],
-        'Z' => ["###", "  #", " # ", "#  ", "###"],
-        '0' => ["###", "# #", "# #", "# #", "###"],
-        '1' => [" # ", "## ", " # ", " # ", "###"],
-        '2' => ["###", "  #", "###", "#  ", "###"],
-        '3' => ["###", "  #", "###", "  #", "###"],
-        '4' => ["# #", "# #", "###", "  #", "  #"],
-        '5' => ["###", "#  ", "###", "  #", "###"],
-        '6' => ["###", "#  ", "###", "# #", "###"],
-        '7' => ["###", "  #", " # ", " # ", " # "],
-        '8' => ["###", "# #", "###", "# #", "###"],
-        '9' => ["###", "# #", "###", "  #", "###"],
-        '!' => [" # ", " # ", " # ", "   ", " # "],
-        '?' => ["###", "  #", " # ", "   ", " # "],
-        '.' => ["   ", "   ", "   ", "   ", " # "],
-        ',' => ["   ", "   ", "   ", " # ", "#  "],
-        '-' => ["   ", "   ", "###", "   ", "   "],
-        '_' => ["   ", "   ", "   ", "   ", "###"],
-        ':' => ["   ", " # ", "   ", " # ", "   "],
-        '/' => ["  #", "  #", " # ", "#  ", "#  "],
-        ' ' => ["   ", "   ", "   ", "   ", "   "],
-        _ => ["###", " # ", "###", " # ", "###"],
+/// Ziel der Terminalausgabe: das Terminal selbst oder mit --record zusätzlich eine Aufnahme.
+enum Output {
+    Terminal(Stdout),
+    Record(CastRecorder<Stdout, BufWriter<File>>),
+}
+
+impl Output {
+    /// Gibt Ausstehendes aus und schließt eine laufende Aufnahme ab.
+    fn finish(self) -> std::io::Result<()> {
+        match self {
+            Output::Terminal(mut out) => out.flush(),
+            Output::Record(recorder) => recorder.finish(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Output::Terminal(out) => out.write(buf),
+            Output::Record(recorder) => recorder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Output::Terminal(out) => out.flush(),
+            Output::Record(recorder) => recorder.flush(),
+        }
+    }
+}
+
+/// Stellt das Terminal beim Verlassen wieder her – auch bei `?`-Fehlern und Panics.
+struct TerminalGuard<W: Write = Stdout> {
+    /// Ziel der Aufräumsequenzen; in Tests ein Puffer statt des Terminals
+    out: W,
+    /// Mausereignisse wurden eingeschaltet und müssen wieder ausgeschaltet werden
+    mouse: bool,
+}
+
+impl TerminalGuard {
+    fn enter(out: &mut impl Write, mouse: bool) -> std::io::Result<Self> {
+        // Ältere Windows-Konsolen verstehen ANSI-Sequenzen erst mit VT-Verarbeitung;
+        // sonst fiele crossterm auf WinAPI-Aufrufe zurück, die in keiner Aufnahme landen
+        #[cfg(windows)]
+        crossterm::ansi_support::supports_ansi();
+        terminal::enable_raw_mode()?;
+        // Ab hier räumt `Drop` auf, selbst wenn die folgenden Befehle scheitern
+        let guard = TerminalGuard {
+            out: stdout(),
+            mouse,
+        };
+        out.execute(terminal::EnterAlternateScreen)?;
+        out.execute(cursor::Hide)?;
+        if mouse {
+            out.execute(event::EnableMouseCapture)?;
+        }
+        Ok(guard)
+    }
+}
+
+impl<W: Write> Drop for TerminalGuard<W> {
+    fn drop(&mut self) {
+        let out = &mut self.out;
+        if self.mouse {
+            let _ = out.execute(event::DisableMouseCapture);
+        }
+        let _ = out.execute(cursor::Show);
+        let _ = out.execute(terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Geschätzte Bytes je Zelle: Cursor-Sprung, Vordergrund-/Hintergrundfarbe,
+/// Zeichen und Rücksetzen der Attribute
+const BYTES_PER_CELL: usize = 48;
+
+/// Puffergröße für einen vollständig neu gezeichneten Frame.
+fn frame_capacity(width: u16, height: u16) -> usize {
+    width as usize * height as usize * BYTES_PER_CELL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Argumente wie auf der Kommandozeile, ohne Profil und Thema
+    fn args(argv: &[&str]) -> Args {
+        Args::try_parse_from(std::iter::once("matrix").chain(argv.iter().copied()))
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    #[test]
+    fn colors_parse_into_list() {
+        // Früher: Absturz beim Zugriff auf `colors`, weil clap jeden Wert einzeln erwartete
+        let args = args(&["--colors", "#ff0000, #00ff00"]);
+        assert_eq!(
+            args.colors,
+            Some(vec!["#ff0000".to_string(), "#00ff00".to_string()])
+        );
+    }
+
+    #[test]
+    fn colors_reject_invalid_entry() {
+        let err = Args::try_parse_from(["matrix", "--colors", "#ff0000,nope"]).unwrap_err();
+        assert!(err.to_string().contains("nope"), "{err}");
+    }
+
+    #[test]
+    fn fps_outside_range_is_rejected() {
+        assert_eq!(args(&["--fps", "30"]).fps, 30);
+        for fps in ["0", "241"] {
+            assert!(
+                Args::try_parse_from(["matrix", "--fps", fps]).is_err(),
+                "{fps}"
+            );
+        }
+    }
+
+    #[test]
+    fn charset_chars_dedup_in_order() {
+        // Früher: Absturz beim Zugriff auf `charset_chars`, wie bei `colors`
+        let args = args(&["--charset-chars", "日本日012"]);
+        assert_eq!(args.charset_chars, Some(vec!['日', '本', '0', '1', '2']));
+    }
+
+    #[test]
+    fn charset_chars_reject_empty() {
+        assert!(Args::try_parse_from(["matrix", "--charset-chars", ""]).is_err());
+        assert!(Args::try_parse_from(["matrix", "--charset-chars", "\t\n"]).is_err());
+    }
+
+    /// Schreibziel, dessen Inhalt den Guard überlebt
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn guard_restores_terminal_on_panic() {
+        let buf = SharedBuf::default();
+        let out = buf.clone();
+        let result = std::panic::catch_unwind(move || {
+            let _guard = TerminalGuard { out, mouse: true };
+            panic!("Absturz mitten im Frame");
+        });
+        assert!(result.is_err());
+        let written = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        // Maus aus, Cursor sichtbar, Alternate Screen verlassen
+        for seq in ["\x1b[?1000l", "\x1b[?25h", "\x1b[?1049l"] {
+            assert!(written.contains(seq), "{seq:?} fehlt in {written:?}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sigterm_sets_the_shutdown_flag() {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        register_shutdown(&shutdown).unwrap();
+        // Mit registriertem Handler beendet das Signal den Testprozess nicht
+        signal_hook::low_level::raise(signal_hook::consts::SIGTERM).unwrap();
+        assert!(shutdown.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn frames_are_separated_by_form_feeds() {
+        let mut state = RainState::with_config(RainConfig::default(), 10, 4);
+        let mut renderer = TextRenderer::new(Vec::new());
+        render_frames(
+            &mut renderer,
+            &mut state,
+            3,
+            Duration::from_millis(16),
+            None,
+        )
+        .unwrap();
+        let text = String::from_utf8(renderer.into_inner()).unwrap();
+        let frames: Vec<&str> = text.split('\x0c').collect();
+        assert_eq!(frames.len(), 3);
+        for frame in frames {
+            assert_eq!(frame.lines().count(), 4, "{frame:?}");
+            assert!(frame.lines().all(|line| line.chars().count() == 10));
+        }
+    }
+
+    #[test]
+    fn grid_size_prefers_overrides_then_detection() {
+        assert_eq!(grid_size(Some(40), Some(12), Some((100, 50))), (40, 12));
+        assert_eq!(grid_size(None, Some(12), Some((100, 50))), (100, 12));
+        assert_eq!(grid_size(None, None, Some((0, 0))), (80, 24));
+        assert_eq!(grid_size(None, None, None), (80, 24));
+    }
+
+    #[test]
+    fn pause_holds_until_a_single_step() {
+        let (dt, frame) = (Duration::from_millis(40), Duration::from_millis(16));
+        assert_eq!(sim_step(false, false, dt, frame), Some(dt));
+        assert_eq!(sim_step(true, false, dt, frame), None);
+        // Ein Einzelschritt rückt nur um einen Frame vor, egal wie lange die Pause dauerte
+        assert_eq!(sim_step(true, true, dt, frame), Some(frame));
+    }
+
+    #[test]
+    fn colorset_cycling_wraps_both_ways() {
+        let n = ColorSetName::value_variants().len();
+        let mut index = None;
+        let mut seen = Vec::new();
+        for _ in 0..n {
+            let next = next_colorset(index, true, n);
+            seen.push(next);
+            index = Some(next);
+        }
+        assert_eq!(seen, (0..n).collect::<Vec<_>>());
+        assert_eq!(next_colorset(index, true, n), 0);
+        assert_eq!(next_colorset(Some(0), false, n), n - 1);
+    }
+
+    #[test]
+    fn colorset_cycling_starts_from_custom_colors() {
+        let n = ColorSetName::value_variants().len();
+        assert_eq!(next_colorset(None, true, n), 0);
+        assert_eq!(next_colorset(None, false, n), n - 1);
+    }
+
+    #[test]
+    fn label_fits_narrow_grid() {
+        let mut grid = Grid::new(4, 2);
+        draw_label(&mut grid, "thermography");
+        assert_eq!(grid.get(0, 0).map(|c| c.ch), Some(' '));
+        assert_eq!(grid.get(1, 0).map(|c| c.ch), Some('t'));
+
+        let mut grid = Grid::new(20, 2);
+        draw_label(&mut grid, "city");
+        let row: String = (0..20)
+            .filter_map(|x| grid.get(x, 0))
+            .map(|c| c.ch)
+            .collect();
+        assert!(row.ends_with(" city "), "{row:?}");
+    }
+
+    #[test]
+    fn headless_run_stops_once_complete() {
+        let config = RainConfig {
+            text: "HI".into(),
+            seed: Some(2),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 30, 10);
+        let mut renderer = TextRenderer::new(Vec::new());
+        let dt = Duration::from_millis(16);
+        render_frames(&mut renderer, &mut state, 100_000, dt, Some(Duration::ZERO)).unwrap();
+        assert!(state.is_complete());
+        assert!(renderer.frames() < 100_000, "{}", renderer.frames());
+    }
+
+    #[test]
+    fn keep_text_rejects_headless_output() {
+        for extra in [
+            ["--frames", "10"],
+            ["--output", "-"],
+            ["--screenshot", "a.png"],
+        ] {
+            let argv = ["matrix", "--keep-text", extra[0], extra[1]];
+            let err = Args::try_parse_from(argv).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::ArgumentConflict, "{extra:?}");
+        }
+    }
+
+    #[test]
+    fn frame_buffer_needs_fewer_writes() {
+        /// Zählt nur die Schreibaufrufe (≈ Syscalls)
+        #[derive(Default)]
+        struct CountingSink(usize);
+
+        impl Write for CountingSink {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0 += 1;
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let (width, height) = (80, 24);
+        let config = RainConfig {
+            seed: Some(7),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, width, height);
+        for _ in 0..120 {
+            state.step(Duration::from_millis(16));
+        }
+        let options = RenderOptions {
+            color: true,
+            ..RenderOptions::default()
+        };
+        let draw = |out: &mut dyn Write| {
+            CrosstermRenderer::new(out, options)
+                .draw_grid(state.grid(), None)
+                .unwrap();
+        };
+        let mut line_sink = CountingSink::default();
+        draw(&mut std::io::LineWriter::new(&mut line_sink));
+        let mut buf_sink = CountingSink::default();
+        draw(&mut BufWriter::with_capacity(
+            frame_capacity(width, height),
+            &mut buf_sink,
+        ));
+        assert!(
+            buf_sink.0 < line_sink.0,
+            "{} >= {}",
+            buf_sink.0,
+            line_sink.0
+        );
+    }
+
+    #[test]
+    fn intro_delay_accepts_fractional_seconds() {
+        assert_eq!(
+            args(&["--intro-delay", "1.5"]).intro_delay,
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(parse_seconds("0"), Ok(Duration::ZERO));
+    }
+
+    #[test]
+    fn intro_delay_rejects_invalid_seconds() {
+        for bad in ["-1", "-0.5", "abc", "", "inf", "NaN", "1s"] {
+            assert!(parse_seconds(bad).is_err(), "{bad:?}");
+        }
+        let err = Args::try_parse_from(["matrix", "--intro-delay", "zwei"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn trail_bounds_reject_invalid_values() {
+        for argv in [
+            ["--trail-min", "0"],
+            ["--trail-max", "65"],
+            ["--trail-min", "x"],
+        ] {
+            let err = Args::try_parse_from(std::iter::once("matrix").chain(argv)).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::ValueValidation, "{argv:?}");
+        }
+        let err = Args::try_parse_from(["matrix", "--trail", "8", "--trail-max", "9"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn scroll_keys_stay_within_bounds() {
+        assert_eq!(step_scroll_speed(4, true), 5);
+        assert_eq!(step_scroll_speed(4, false), 3);
+        assert_eq!(step_scroll_speed(10, true), 10);
+        assert_eq!(step_scroll_speed(0, false), 0);
+    }
+
+    #[test]
+    fn left_click_reports_position() {
+        let click = |kind| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column: 10,
+                row: 6,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        assert_eq!(
+            left_click(&click(MouseEventKind::Down(MouseButton::Right))),
+            None
+        );
+        assert_eq!(left_click(&click(MouseEventKind::Moved)), None);
+        assert_eq!(
+            left_click(&click(MouseEventKind::Down(MouseButton::Left))),
+            Some((10, 6))
+        );
+    }
+
+    #[test]
+    fn color_flags_name_the_reason() {
+        let err = parse_color("#gg0000").unwrap_err();
+        assert!(err.contains("`g` ist keine Hexadezimalziffer"), "{err}");
+        let err = parse_hex_list("#ff0000, #1234567").unwrap_err();
+        assert!(
+            err.contains("#1234567") && err.contains("7 Ziffern"),
+            "{err}"
+        );
+        assert_eq!(parse_border_color("AUTO"), Ok(BorderColor::Auto));
+        assert!(parse_border_color("grün").is_err());
+    }
+
+    #[test]
+    fn duration_converts_to_frames() {
+        let at = |fps: u64| Duration::from_millis(1000 / fps);
+        assert_eq!(frames_within(Duration::from_secs(2), at(20)), 40);
+        // Ein angebrochener Frame zählt mit
+        assert_eq!(frames_within(Duration::from_millis(110), at(20)), 3);
+        assert_eq!(frames_within(Duration::ZERO, at(20)), 0);
+        // Mehr Frames, als in `u32` passen, sättigen
+        assert_eq!(
+            frames_within(Duration::from_secs(10), Duration::ZERO),
+            u32::MAX
+        );
+    }
+
+    #[test]
+    fn zero_size_is_rejected() {
+        for argv in [
+            ["--width", "0", "--height", "0"],
+            ["--width", "0", "--height", "24"],
+            ["--width", "80", "--height", "0"],
+        ] {
+            let err = Args::try_parse_from(std::iter::once("matrix").chain(argv)).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::ValueValidation, "{argv:?}");
+        }
+    }
+
+    #[test]
+    fn zero_sized_terminal_waits_for_resize() {
+        assert!(no_room_in(None, None, (0, 0)));
+        assert!(no_room_in(None, Some(24), (0, 30)));
+        assert!(no_room_in(Some(80), None, (100, 0)));
+        // Überschreibungen gelten unabhängig von der erkannten Größe
+        assert!(!no_room_in(Some(80), Some(24), (0, 0)));
+        assert!(!no_room_in(None, None, (80, 24)));
+    }
+
+    #[test]
+    fn charset_weights_parse_pairs() {
+        assert_eq!(
+            parse_charset_weights("0=9,1=1"),
+            Ok(vec![('0', 9), ('1', 1)])
+        );
+        // `=` als Zeichen: getrennt wird am letzten `=`
+        assert_eq!(parse_charset_weights("==3"), Ok(vec![('=', 3)]));
+        for bad in ["0", "01=2", "=2", "0=-1", "0=x", ""] {
+            assert!(parse_charset_weights(bad).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn all_zero_weights_are_rejected() {
+        let binary = ['0', '1'];
+        assert!(hides_every_glyph(&binary, &[('0', 0), ('1', 0)]));
+        // Eine spätere Angabe überschreibt die frühere
+        assert!(!hides_every_glyph(&binary, &[('0', 0), ('1', 0), ('1', 2)]));
+        // Nicht genannte Zeichen wiegen 1
+        assert!(!hides_every_glyph(&binary, &[('0', 0)]));
+        assert!(!hides_every_glyph(&binary, &[('x', 0)]));
+    }
+
+    #[test]
+    fn frame_sleep_shrinks_with_work() {
+        let budget = Duration::from_millis(40);
+        assert_eq!(frame_sleep(budget, Duration::ZERO), budget);
+        assert_eq!(
+            frame_sleep(budget, Duration::from_millis(20)),
+            Duration::from_millis(20)
+        );
+        assert_eq!(
+            frame_sleep(budget, Duration::from_millis(55)),
+            Duration::ZERO
+        );
+    }
+
+    /// Argumente samt Treffern, wie `parse_args` sie ohne Profil und Thema liefert
+    fn parsed(argv: impl IntoIterator<Item = OsString>) -> (Args, ArgMatches) {
+        let argv = std::iter::once(OsString::from("matrix")).chain(argv);
+        let matches = Args::command()
+            .try_get_matches_from(argv)
+            .unwrap_or_else(|err| panic!("{err}"));
+        let args = Args::from_arg_matches(&matches).unwrap();
+        (args, matches)
+    }
+
+    #[test]
+    fn print_config_loads_as_profile() {
+        let argv = [
+            "--fps",
+            "30",
+            "--colors",
+            "#ff0000,#00ff00",
+            "--density",
+            "1",
+            "--charset",
+            "katakana",
+            "--speed-min",
+            "20",
+        ];
+        let (args, matches) = parsed(argv.map(OsString::from));
+        let printed = resolved_config(&args, &matches);
+        let profile: Profile =
+            toml::from_str(&printed).unwrap_or_else(|err| panic!("{err}\n{printed}"));
+        assert_eq!(
+            profile.colors,
+            Some(vec!["#ff0000".into(), "#00ff00".into()])
+        );
+        assert_eq!(profile.density, Some(1.0));
+        assert_eq!(profile.fps, Some(30));
+        assert_eq!(profile.charset.as_deref(), Some("katakana"));
+        // Was kein Profil setzen kann, bleibt als Kommentar sichtbar
+        assert!(printed.contains("\n# speed-min = 20\n"), "{printed}");
+
+        // Das geladene Profil ergibt wieder dieselben Werte
+        let (reloaded, _) = parsed(profile.to_args(|_| false));
+        assert_eq!(resolved_profile(&reloaded), profile);
+    }
+
+    #[test]
+    fn print_config_leaves_multiple_strings_to_comments() {
+        let (args, matches) = parsed(["-s", "A", "-s", "B"].map(OsString::from));
+        let printed = resolved_config(&args, &matches);
+        let profile: Profile = toml::from_str(&printed).unwrap();
+        assert_eq!(profile.string, None);
+        assert!(printed.contains("# string = [\"A\", \"B\"]"), "{printed}");
+    }
+
+    #[test]
+    fn stats_count_frames_per_window_and_own_cells() {
+        let start = Instant::now();
+        let mut stats = FrameStats::new(start);
+        for frame in 1..30 {
+            stats.record(start + Duration::from_millis(frame * 1000 / 30), 50, 10);
+        }
+        // Das erste Messfenster ist noch nicht voll
+        assert_eq!(stats.line(40), "0 fps  40 col  40 cells");
+        stats.record(start + STATS_WINDOW, 120, 10);
+        assert_eq!(stats.line(40), "30 fps  40 col  110 cells");
+    }
+
+    #[test]
+    fn stats_line_skips_reserved_cells() {
+        let prev = Grid::new(12, 3);
+        let mut grid = Grid::new(12, 3);
+        draw_stats(&mut grid, "9 fps", |x, _| x == 3);
+        let top: String = grid.rows().next().unwrap().iter().map(|c| c.ch).collect();
+        assert_eq!(top.trim_end(), " 9  ps");
+        // Die freigelassene Zelle zählt nicht mit
+        assert_eq!(overlay_cells(&grid, Some(&prev), 7), 6);
+        assert_eq!(overlay_cells(&grid, None, 7), 7);
     }
 }
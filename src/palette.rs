@@ -0,0 +1,125 @@
+use crate::color::ColorSet;
+use crossterm::style::Color;
+use std::fmt;
+use std::path::Path;
+
+/// Fehler beim Laden einer GIMP-Palette (`.gpl`).
+#[derive(Debug)]
+pub enum GplError {
+    Io(std::io::Error),
+    /// Erste Zeile ist nicht `GIMP Palette`
+    Header,
+    /// Keine einzige gültige Farbzeile
+    Empty,
+}
+
+impl fmt::Display for GplError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GplError::Io(err) => write!(f, "Palette nicht lesbar: {err}"),
+            GplError::Header => write!(f, "keine GIMP-Palette (Kopfzeile `GIMP Palette` fehlt)"),
+            GplError::Empty => write!(f, "Palette enthält keine gültige Farbe"),
+        }
+    }
+}
+
+impl std::error::Error for GplError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GplError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for GplError {
+    fn from(err: std::io::Error) -> Self {
+        GplError::Io(err)
+    }
+}
+
+/// Farbpalette im GIMP-Format: Kopfzeile `GIMP Palette`, optional `Name:` und
+/// `Columns:`, dann je Zeile `R G B Name` mit Kanälen 0–255; `#` leitet Kommentare ein.
+///
+/// ```
+/// use matrix::{Color, GimpPalette};
+///
+/// let src = "GIMP Palette\nName: Grün\nColumns: 2\n# Kommentar\n\
+///            0 59 0\tDunkel\n0 143 17 Mitte\nkaputt\n0 255 65\tHell\n";
+/// let palette = GimpPalette::parse(src)?;
+///
+/// assert_eq!(palette.name.as_deref(), Some("Grün"));
+/// assert_eq!(
+///     palette.colors,
+///     [
+///         Color::Rgb { r: 0, g: 59, b: 0 },
+///         Color::Rgb { r: 0, g: 143, b: 17 },
+///         Color::Rgb { r: 0, g: 255, b: 65 },
+///     ]
+/// );
+/// // Zeile 7 ist keine Farbe und wird übersprungen
+/// assert_eq!(palette.skipped, [7]);
+/// # Ok::<(), matrix::GplError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct GimpPalette {
+    /// Wert der `Name:`-Zeile
+    pub name: Option<String>,
+    /// Farben in Dateireihenfolge
+    pub colors: Vec<Color>,
+    /// Zeilennummern (ab 1) ungültiger Zeilen, die übersprungen wurden
+    pub skipped: Vec<usize>,
+}
+
+impl GimpPalette {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GplError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parst den Inhalt einer `.gpl`-Datei; ungültige Farbzeilen landen in `skipped`.
+    pub fn parse(src: &str) -> Result<Self, GplError> {
+        let mut lines = src.lines();
+        if lines.next().map(str::trim) != Some("GIMP Palette") {
+            return Err(GplError::Header);
+        }
+        let mut palette = Self {
+            name: None,
+            colors: Vec::new(),
+            skipped: Vec::new(),
+        };
+        for (i, line) in (2..).zip(lines) {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("Columns:") {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("Name:") {
+                palette.name = Some(name.trim().to_string());
+                continue;
+            }
+            match parse_rgb(line) {
+                Some(color) => palette.colors.push(color),
+                None => palette.skipped.push(i),
+            }
+        }
+        if palette.colors.is_empty() {
+            return Err(GplError::Empty);
+        }
+        Ok(palette)
+    }
+
+    /// Farbset mit den Palettenfarben als Verlauf, in Dateireihenfolge.
+    pub fn color_set(&self) -> ColorSet {
+        ColorSet::from_rgba(self.colors.iter().map(|&c| (c, u8::MAX)).collect())
+    }
+}
+
+/// Die ersten drei Felder als Kanäle 0–255; der Rest ist der Farbname.
+fn parse_rgb(line: &str) -> Option<Color> {
+    let mut fields = line.split_whitespace();
+    let mut channel = || fields.next()?.parse::<u8>().ok();
+    Some(Color::Rgb {
+        r: channel()?,
+        g: channel()?,
+        b: channel()?,
+    })
+}
@@ -0,0 +1,147 @@
+//! Profil-Datei (`config.toml`) mit Voreinstellungen für die Kommandozeile.
+//!
+//! Rangfolge: Flags auf der Kommandozeile > Profil-Datei > eingebaute Standardwerte.
+
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Optionen, die ein Profil setzen darf; Namen wie die langen Flags.
+/// Beim Schreiben (`--print-config`) fallen leere Felder weg.
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Profile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub string: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colorset: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colors: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fps: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trail: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub charset: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub density: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<String>,
+}
+
+/// Fehler beim Lesen oder Parsen einer Profil-Datei.
+#[derive(Debug)]
+pub enum ProfileError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::Io(path, err) => write!(f, "{}: {err}", path.display()),
+            ProfileError::Parse(path, err) => write!(f, "{}: {err}", path.display()),
+        }
+    }
+}
+
+impl Profile {
+    pub fn load(path: &Path) -> Result<Self, ProfileError> {
+        let src =
+            std::fs::read_to_string(path).map_err(|e| ProfileError::Io(path.to_path_buf(), e))?;
+        toml::from_str(&src).map_err(|e| ProfileError::Parse(path.to_path_buf(), e))
+    }
+
+    /// `~/.config/matrixrain/config.toml` (bzw. unter `$XDG_CONFIG_HOME`), falls vorhanden.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+        let path = base.join("matrixrain").join("config.toml");
+        path.is_file().then_some(path)
+    }
+
+    /// Übersetzt das Profil in Flags, damit es dieselbe Prüfung wie die Kommandozeile
+    /// durchläuft. `skip` meldet Optionen, die schon auf der Kommandozeile stehen.
+    pub fn to_args(&self, skip: impl Fn(&str) -> bool) -> Vec<OsString> {
+        let mut args = Vec::new();
+        let mut push = |id: &str, value: String| {
+            if !skip(id) {
+                args.push(format!("--{}", id.replace('_', "-")).into());
+                args.push(value.into());
+            }
+        };
+        // --string-file ersetzt den Ziel-String des Profils ebenfalls
+        if let Some(v) = &self.string
+            && !skip("string_file")
+        {
+            push("string", v.clone());
+        }
+        // --colors, --colorset und --palette-file schließen sich aus; steht eins davon
+        // auf der Kommandozeile, bleibt das Farbset des Profils außen vor
+        let color_on_cli =
+            skip("colorset") || skip("colors") || skip("palette_file") || skip("list");
+        if !color_on_cli {
+            if let Some(v) = &self.colorset {
+                push("colorset", v.clone());
+            }
+            if let Some(v) = &self.colors {
+                push("colors", v.join(","));
+            }
+        }
+        if let Some(v) = self.fps {
+            push("fps", v.to_string());
+        }
+        // --trail-max schließt --trail aus und ersetzt die Spurlänge des Profils
+        if let Some(v) = self.trail
+            && !skip("trail_max")
+        {
+            push("trail", v.to_string());
+        }
+        if let Some(v) = &self.charset {
+            push("charset", v.clone());
+        }
+        if let Some(v) = self.density {
+            push("density", v.to_string());
+        }
+        if let Some(v) = &self.direction {
+            push("direction", v.clone());
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_yields_flags_not_given_on_the_command_line() {
+        let profile: Profile =
+            toml::from_str("fps = 30\ncolors = [\"#ff0000\", \"#00ff00\"]\ndensity = 0.5\n")
+                .unwrap();
+        assert_eq!(
+            profile.to_args(|_| false),
+            [
+                "--colors",
+                "#ff0000,#00ff00",
+                "--fps",
+                "30",
+                "--density",
+                "0.5"
+            ]
+        );
+        // --colorset auf der Kommandozeile verdrängt auch die Farbliste des Profils
+        assert_eq!(
+            profile.to_args(|id| id == "colorset" || id == "fps"),
+            ["--density", "0.5"]
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(toml::from_str::<Profile>("speed = 3").is_err());
+    }
+}
@@ -0,0 +1,3515 @@
+use crate::charset::Charset;
+use crate::color::{ColorSet, ColorSetName, blend_color, hsv_to_color, rotate_hue};
+use crate::figlet::FigletFont;
+use crate::font::{Font, render_text_spaced};
+use clap::ValueEnum;
+use crossterm::style::Color;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
+use std::fmt::{self, Write};
+use std::rc::Rc;
+use std::time::Duration;
+use unicode_width::UnicodeWidthChar;
+
+/// Platzhalter für die zweite Terminalzelle eines breiten Zeichens
+const WIDE_CONT: char = '\0';
+
+/// Zeichen der Schreibmarke bei [`RainConfig::cursor`]
+const CURSOR_GLYPH: char = '█';
+
+/// Fließrichtung des Regens.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Direction {
+    #[default]
+    Down,
+    Up,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn is_vertical(self) -> bool {
+        matches!(self, Direction::Down | Direction::Up)
+    }
+
+    /// Bildet Spur `lane` und Position `pos` entlang der Fließachse der Länge `flow_len`
+    /// auf Rasterkoordinaten ab.
+    fn to_xy(self, lane: u16, pos: u16, flow_len: u16) -> (u16, u16) {
+        let back = flow_len.saturating_sub(1).saturating_sub(pos);
+        match self {
+            Direction::Down => (lane, pos),
+            Direction::Up => (lane, back),
+            Direction::Right => (pos, lane),
+            Direction::Left => (back, lane),
+        }
+    }
+}
+
+/// Darstellungsart des Regens.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Style {
+    /// Nur Regen, ohne Ziel-Text und Rahmen
+    Simple,
+    /// Regen um den gerahmten Ziel-Text; jede Spur trägt ihren Farbverlauf
+    #[default]
+    Block,
+    /// Wie `Block`, aber nur der Kopf schreibt Zeichen; die Farbe ergibt sich aus dem
+    /// Alter der Zelle und verläuft über die Spurlänge, die ältesten Zellen in der letzten Farbe
+    Aged,
+}
+
+/// Wonach sich die Position im Farbverlauf einer Regenzelle richtet.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Gradient {
+    /// Entlang jeder Spur: Kopf am Ende des Verlaufs, Schwanz am Anfang
+    #[default]
+    Trail,
+    /// Nach der Bildschirmzeile: oben Anfang, unten Ende des Verlaufs
+    Screen,
+    /// Nach der Bildschirmspalte: links Anfang, rechts Ende des Verlaufs
+    Column,
+}
+
+/// Wie eingeloggte Zeichen des Ziel-Texts erscheinen.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Reveal {
+    /// Sofort weiß, sobald ein Tropfen das Zeichen trifft
+    #[default]
+    Instant,
+    /// Über [`REVEAL_FADE`] von Grau zu Weiß überblenden
+    Fade,
+    /// Jeder Treffer loggt das nächste Zeichen in Lesereihenfolge ein, egal wo er landet
+    Typewriter,
+}
+
+/// Senkrechte Lage des Ziel-Texts.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TextAlign {
+    /// Oben, mit einer Zeile Platz für den Rahmen
+    Top,
+    /// Mittig
+    #[default]
+    Center,
+    /// Unten, mit einer Zeile Platz für den Rahmen
+    Bottom,
+    /// Oberste Textzeile in dieser Bildschirmzeile, begrenzt auf die Zeilen, in die der
+    /// Text samt Rahmen passt
+    Row(u16),
+}
+
+/// Waagerechte Lage des Ziel-Texts.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TextHAlign {
+    /// Links, mit einer Spalte Platz für den Rahmen
+    Left,
+    /// Mittig
+    #[default]
+    Center,
+    /// Rechts, mit einer Spalte Platz für den Rahmen
+    Right,
+}
+
+/// Weiterer Ziel-Text neben [`RainConfig::text`], mit eigener Ausrichtung.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextBlock {
+    /// Ziel-String; `\n` trennt Zeilen
+    pub text: String,
+    /// Senkrechte Lage
+    pub align: TextAlign,
+    /// Waagerechte Lage
+    pub halign: TextHAlign,
+}
+
+/// Erste Position, mittige Position und letzte Position eines Blocks der Länge `len`
+/// in `room` Zellen; die äußeren lassen nach Möglichkeit eine Zelle für den Rahmen frei.
+fn block_starts(len: u16, room: u16) -> (u16, u16, u16) {
+    let center = (room.saturating_sub(len) / 2).min(room.saturating_sub(1));
+    let first = center.min(1);
+    let last = room.saturating_sub(len.saturating_add(1)).max(first);
+    (first, center, last)
+}
+
+/// Dauer der Überblendung bei [`Reveal::Fade`]
+pub const REVEAL_FADE: Duration = Duration::from_millis(300);
+
+/// Frames, die die Schreibmarke bei [`RainConfig::cursor`] jeweils sichtbar bzw.
+/// unsichtbar bleibt
+pub const CURSOR_BLINK: usize = 30;
+
+/// Größter Mischanteil bei [`RainConfig::depth_shading`] für die schnellste bzw.
+/// langsamste Spur
+pub const DEPTH_SHADING: f32 = 0.5;
+
+/// Faktor, mit dem [`RainConfig::gravity`] die Verweildauer einer Spur je Schritt verkürzt
+pub const GRAVITY: f32 = 0.96;
+
+/// Mischanteil Richtung Weiß, mit dem der Kopf ohne [`RainConfig::bold_head`] statt
+/// fett gezeichnet wird
+pub const HEAD_BRIGHTEN: f32 = 0.5;
+
+/// Größter Mischanteil Richtung Weiß, mit dem eine Zelle bei [`RainConfig::wake`] direkt
+/// nach dem Kopf nachleuchtet
+pub const WAKE_GLOW: f32 = 0.6;
+
+/// Spuren zu jeder Seite, die [`RainState::burst`] neu startet
+pub const BURST_RADIUS: u16 = 2;
+
+/// Dauer des Funkelns bei [`RainConfig::celebrate`]
+pub const CELEBRATION: Duration = Duration::from_secs(1);
+
+/// Abstand, bis zu dem die Funken bei [`RainConfig::celebrate`] um den Rahmen fallen
+const SPARKLE_MARGIN: u16 = 2;
+
+/// Zeichen eines Funkens bei [`RainConfig::celebrate`]
+const SPARKLE_GLYPH: char = '*';
+
+/// Höchstens so viele Schritte vor dem Eintrittsrand startet eine Spur bei
+/// [`RainConfig::continuous`] neu
+const CONTINUOUS_DELAY: i32 = 3;
+
+/// Zeichensatz des Rahmens um den Ziel-Text.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BorderStyle {
+    /// `+`, `-`, `|`
+    #[default]
+    Ascii,
+    /// `╭╮╰╯─│`
+    Rounded,
+    /// `╔╗╚╝═║`
+    Double,
+    /// Kein Rahmen; der Bereich bleibt trotzdem frei vom Regen
+    None,
+}
+
+/// Farbe des Rahmens um den Ziel-Text.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BorderColor {
+    /// Feste Farbe
+    Fixed(Color),
+    /// Mitte des aktiven Farbsets (`gradient_color(0.5)`)
+    Auto,
+}
+
+impl Default for BorderColor {
+    fn default() -> Self {
+        BorderColor::Fixed(Color::DarkGrey)
+    }
+}
+
+/// Glyphen eines Rahmenstils.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BorderGlyphs {
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+    pub horizontal: char,
+    pub vertical: char,
+}
+
+impl BorderStyle {
+    /// Ecken- und Kantenzeichen; `None` bei [`BorderStyle::None`].
+    pub fn glyphs(self) -> Option<BorderGlyphs> {
+        let [
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+            horizontal,
+            vertical,
+        ] = match self {
+            BorderStyle::Ascii => ['+', '+', '+', '+', '-', '|'],
+            BorderStyle::Rounded => ['╭', '╮', '╰', '╯', '─', '│'],
+            BorderStyle::Double => ['╔', '╗', '╚', '╝', '═', '║'],
+            BorderStyle::None => return None,
+        };
+        Some(BorderGlyphs {
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+            horizontal,
+            vertical,
+        })
+    }
+}
+
+/// Rückruf nach jedem [`RainState::step`] mit dem neuen Zustand und der Nummer des
+/// Frames (ab 0), etwa für Aufnahmen, Ereignisse an bestimmten Frames oder eigene
+/// Überlagerungen. Kopien von [`RainConfig`] teilen sich denselben Rückruf.
+///
+/// ```
+/// use matrix::{FrameHook, RainConfig, RainState};
+/// use std::rc::Rc;
+/// use std::time::Duration;
+///
+/// let calls = Rc::new(std::cell::Cell::new(0));
+/// let seen = Rc::clone(&calls);
+/// let config = RainConfig {
+///     on_frame: Some(FrameHook::new(move |_state, frame| {
+///         assert_eq!(frame, seen.get());
+///         seen.set(frame + 1);
+///     })),
+///     ..RainConfig::default()
+/// };
+/// let mut state = RainState::with_config(config, 20, 10);
+/// for _ in 0..5 {
+///     state.step(Duration::from_millis(16));
+/// }
+/// assert_eq!(calls.get(), 5);
+/// ```
+#[derive(Clone)]
+pub struct FrameHook(Rc<RefCell<FrameFn>>);
+
+type FrameFn = dyn FnMut(&RainState, usize);
+
+impl FrameHook {
+    pub fn new(hook: impl FnMut(&RainState, usize) + 'static) -> Self {
+        Self(Rc::new(RefCell::new(hook)))
+    }
+}
+
+impl fmt::Debug for FrameHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FrameHook(..)")
+    }
+}
+
+/// Einstellungen der Simulation.
+#[derive(Clone, Debug)]
+pub struct RainConfig {
+    /// Ziel-String, in dem die fallenden Zeichen „einrasten“; `\n` trennt Zeilen
+    pub text: String,
+    /// Schrift für den Ziel-Text
+    pub font: Font,
+    /// Leerspalten zwischen zwei Glyphen der eingebauten Schriften (0 = aneinander);
+    /// FIGlet-Schriften bringen ihren Abstand selbst mit
+    pub letter_spacing: u16,
+    /// Leerzeilen zwischen den Blöcken mehrzeiliger Ziel-Texte
+    pub line_spacing: u16,
+    /// FIGlet-Schrift, die statt `font` verwendet wird
+    pub figlet: Option<FigletFont>,
+    /// Farbverlauf der Spuren
+    pub colorset: ColorSet,
+    /// Eigener Zwei-Farben-Verlauf `(Kopf, Schwanz)` nur für die Spuren; ersetzt
+    /// dort die Farben von `colorset`, dessen Farbraum, Hintergrund, Helligkeit und
+    /// Kontrast bleiben. Ziel-Text, Rahmen und Hintergrundschicht behalten ihre Farben
+    pub trail_gradient: Option<(Color, Color)>,
+    /// Verlauf entlang der Spur umdrehen: der Kopf bekommt den Anfang des Verlaufs, das
+    /// Spurende dessen Ende; gilt nur für [`Gradient::Trail`]
+    pub reverse_gradient: bool,
+    /// Hintergrund-Verschiebungsgeschwindigkeit (0 = aus, 10 = am schnellsten)
+    pub scroll_speed: u8,
+    /// Laufschrift: der Ziel-Text wandert mit dieser Geschwindigkeit (1–10, wie
+    /// `scroll_speed`) nach links und läuft rechts wieder ein; er leuchtet sofort
+    /// vollständig, ohne Rahmen und ohne Einloggen, der Regen fällt dahinter
+    pub marquee: Option<u8>,
+    /// Anzahl gezeichneter Zellen pro Spur inklusive Kopf (mindestens 1); mit
+    /// `trail_min` die größte Spurlänge
+    pub trail_len: u16,
+    /// Kleinste Spurlänge; mit Wert würfelt jede Spur bei jedem Start eine eigene
+    /// Länge zwischen `trail_min` und `trail_len` (inklusive)
+    pub trail_min: Option<u16>,
+    /// Zeichen, aus denen der Regen besteht (leer = [`Charset::Ascii`]).
+    /// Jedes Zeichen belegt eine Rasterzelle; breite Glyphen (CJK) ragen im Terminal
+    /// in die Nachbarzelle und werden in der letzten Spalte nicht gezeichnet.
+    pub charset: Vec<char>,
+    /// Gewicht einzelner Zeichen von `charset`: ein Zeichen mit Gewicht 3 erscheint
+    /// dreimal so oft wie eines mit Gewicht 1. Nicht genannte Zeichen wiegen 1, Zeichen
+    /// außerhalb von `charset` zählen nicht; leer = alle gleich häufig
+    pub charset_weights: Vec<(char, u32)>,
+    /// Fließrichtung; bei `Left`/`Right` gibt es eine Spur pro Zeile statt pro Spalte
+    pub direction: Direction,
+    /// Startwert für den Zufallsgenerator; gleiche Werte ergeben gleiche Animationen
+    pub seed: Option<u64>,
+    /// Wahrscheinlichkeit (0.0–1.0), mit der eine abgelaufene Spur pro Schritt neu startet.
+    /// Bei 1.0 ist jede Spur ständig aktiv und das Zeichenfeld bleibt stehen; darunter
+    /// löschen die Spuren ihr Ende, sodass bei 0.0 der Regen nach und nach verschwindet.
+    pub density: f32,
+    /// Abgelaufene Spuren starten sofort wieder, nur bis zu wenige Schritte vor dem
+    /// Eintrittsrand statt weit davor, und ohne die Wartezeit von `density`
+    pub continuous: bool,
+    /// Kleinste und größte Verweildauer pro Spurschritt in Millisekunden (inklusive);
+    /// kleinere Werte lassen die Tropfen schneller fallen
+    pub speed_min: u64,
+    pub speed_max: u64,
+    /// Darstellungsart
+    pub style: Style,
+    /// Aufhellung des Kopfes Richtung Weiß (0.0 = aus, 1.0 = weiß); die zwei
+    /// folgenden Zellen leuchten abgeschwächt mit
+    pub glow: f32,
+    /// Wahrscheinlichkeit (0.0–1.0), dass eine gezeichnete Spurzelle ein zufälliges
+    /// Zeichen statt des nächsten aus der Rotation zeigt; der Ziel-Text flackert nie
+    pub flicker: f32,
+    /// Erscheinen des Ziel-Texts
+    pub reveal: Reveal,
+    /// Senkrechte Lage des Ziel-Texts
+    pub text_align: TextAlign,
+    /// Waagerechte Lage des Ziel-Texts
+    pub text_halign: TextHAlign,
+    /// Weitere Ziel-Texte, jeder als eigener Block mit eigener Ausrichtung, eigenem Rahmen
+    /// und eigenem Einlog-Zustand; sie sollten sich weder mit `text` noch untereinander
+    /// überlappen (siehe [`RainState::targets_overlap`])
+    pub extra_texts: Vec<TextBlock>,
+    /// Regenbogen-Modus: Periode in Frames, in der der Farbton einmal über das
+    /// HSV-Rad läuft; ersetzt das Farbset
+    pub rainbow: Option<u32>,
+    /// Größte zufällige Farbtonverschiebung je Spur in Grad (0 = alle Spuren gleich);
+    /// gilt für die Spurfarben, nicht für [`Style::Aged`]
+    pub column_hue: f32,
+    /// Verlaufsrichtung der Spurfarben; gilt nicht für [`Style::Aged`]
+    pub gradient: Gradient,
+    /// Umgekehrter Modus: Regen fällt nur innerhalb der Glyphen des Ziel-Texts,
+    /// außerhalb bleibt es dunkel; ohne Wirkung bei [`Style::Simple`]
+    pub invert: bool,
+    /// Vorlauf, in dem der Regen ungehindert auch durch den Ziel-Bereich fällt und
+    /// noch nichts einloggt; danach erscheinen Rahmen und Ziel-Text
+    pub intro_delay: Duration,
+    /// Tiefenwirkung: schnelle Spuren werden Richtung Verlaufsende aufgehellt, langsame
+    /// in den Hintergrund abgedunkelt, jeweils bis [`DEPTH_SHADING`]
+    pub depth_shading: bool,
+    /// Kopf fett zeichnen; sonst nur um [`HEAD_BRIGHTEN`] Richtung Weiß aufgehellt, für
+    /// Schriften, deren fette Glyphen breiter sind
+    pub bold_head: bool,
+    /// Einblenden beim Start: die Spurfarben steigen über diese Dauer vom Hintergrund
+    /// auf volle Helligkeit (0 = aus)
+    pub fade_in: Duration,
+    /// Frames zwischen zwei Schritten der Zeichenrotation (1 = jedes Frame, 0 = aus);
+    /// ohne Rotation wechseln Zeichen nur noch mit dem Tropfen selbst
+    pub shimmer: u16,
+    /// Schwerkraft: Tropfen beschleunigen im Fall (Verweildauer je Schritt mal
+    /// [`GRAVITY`]) und starten nach dem Neustart wieder mit ihrer Grundgeschwindigkeit
+    pub gravity: bool,
+    /// Zufälliger Versatz (± Millisekunden) der Verweildauer jedes einzelnen Schritts,
+    /// damit die Spuren auch nach langer Laufzeit nicht im Gleichschritt fallen (0 = aus)
+    pub jitter: u16,
+    /// Helligkeit über die Zeichendichte statt über den Zeichensatz zeigen: Spurzellen
+    /// nehmen das Zeichen aus [`SHADING_RAMP`] zu ihrer Position, der Kopf das dichteste
+    pub ascii_shading: bool,
+    /// Spiegelung: die rechte Bildhälfte zeigt den Regen der linken seitenverkehrt;
+    /// Ziel-Text und Rahmen bleiben ungespiegelt
+    pub mirror: bool,
+    /// Deckkraft (0.0–1.0) noch offener Zeichen des Ziel-Texts zwischen Hintergrund und
+    /// Weiß; ohne Wert dunkelgrau
+    pub ghost_alpha: Option<f32>,
+    /// Hintergrundschicht: leere Zellen zeigen feste, schwach leuchtende Zeichen mit
+    /// dieser Helligkeit (0.0–1.0) zwischen Hintergrund und Mitte des Verlaufs
+    pub ambient: Option<f32>,
+    /// Nachleuchten: vom Kopf überstrichene Zellen leuchten heller (bis [`WAKE_GLOW`]) und
+    /// behalten je Frame diesen Anteil (0.0–1.0) ihres Leuchtens
+    pub wake: Option<f32>,
+    /// Zeitgesteuertes Einloggen mit so vielen Wörtern (je fünf Zeichen) pro Minute in
+    /// Lesereihenfolge, unabhängig von den Tropfen; ersetzt `reveal` bis auf `Fade`
+    pub reveal_wpm: Option<u32>,
+    /// Blinkende Block-Schreibmarke über den noch offenen Zellen der nächsten
+    /// Glyphenspalte in Lesereihenfolge; gedacht für `Reveal::Typewriter` und `reveal_wpm`
+    pub cursor: bool,
+    /// Entschlüsseln: eine frisch eingeloggte Zelle zeigt so viele Frames lang zufällige
+    /// Zeichen aus `charset`, bevor das echte Zeichen stehen bleibt; vollständig ist der
+    /// Ziel-Text erst danach
+    pub decrypt: Option<u8>,
+    /// Ist der Ziel-Text vollständig, hält der Regen für [`CELEBRATION`] an und rund um
+    /// den Rahmen blitzen weiße Funken auf; gilt nicht bei `marquee`
+    pub celebrate: bool,
+    /// Rahmenstil um den Ziel-Text
+    pub border: BorderStyle,
+    /// Rahmenfarbe
+    pub border_color: BorderColor,
+    /// Wird nach jedem [`RainState::step`] aufgerufen
+    pub on_frame: Option<FrameHook>,
+}
+
+impl RainConfig {
+    /// Alle Ziel-Texte als Blöcke: zuerst `text`, dann `extra_texts`.
+    fn text_blocks(&self) -> impl Iterator<Item = TextBlock> + '_ {
+        let main = TextBlock {
+            text: self.text.clone(),
+            align: self.text_align,
+            halign: self.text_halign,
+        };
+        std::iter::once(main).chain(self.extra_texts.iter().cloned())
+    }
+
+    /// Geschwindigkeitsspanne, auch bei vertauschten Grenzen oder 0 gültig.
+    fn speed_range(&self) -> std::ops::RangeInclusive<u64> {
+        let lo = self.speed_min.min(self.speed_max).max(1);
+        let hi = self.speed_min.max(self.speed_max).max(1);
+        lo..=hi
+    }
+
+    /// Verlauf für die Spuren: `trail_gradient` als Farbset, sonst `colorset`.
+    fn rain_colors(&self) -> ColorSet {
+        match self.trail_gradient {
+            Some((head, tail)) => self.colorset.with_colors(vec![tail, head]),
+            None => self.colorset.clone(),
+        }
+    }
+
+    /// Spanne der Spurlängen, mindestens 1 und höchstens `trail_len`.
+    fn trail_range(&self) -> std::ops::RangeInclusive<u16> {
+        let hi = self.trail_len.max(1);
+        let lo = self.trail_min.unwrap_or(hi).clamp(1, hi);
+        lo..=hi
+    }
+
+    /// Spurlänge für eine neu startende Spur; ohne `trail_min` ohne Zufallszug.
+    fn roll_trail_len(&self, rng: &mut StdRng) -> u16 {
+        match self.trail_min {
+            Some(_) => rng.gen_range(self.trail_range()),
+            None => self.trail_len.max(1),
+        }
+    }
+}
+
+impl Default for RainConfig {
+    fn default() -> Self {
+        Self {
+            text: "Hallo Welt!".to_string(),
+            font: Font::F3x5,
+            letter_spacing: 1,
+            line_spacing: 1,
+            figlet: None,
+            colorset: ColorSet::from_name(ColorSetName::Determination),
+            trail_gradient: None,
+            reverse_gradient: false,
+            scroll_speed: 5,
+            marquee: None,
+            trail_len: 10,
+            trail_min: None,
+            charset: Charset::default().chars(),
+            charset_weights: Vec::new(),
+            direction: Direction::Down,
+            seed: None,
+            density: 1.0,
+            continuous: false,
+            speed_min: 40,
+            speed_max: 120,
+            style: Style::Block,
+            glow: 0.0,
+            flicker: 0.0,
+            reveal: Reveal::Instant,
+            text_align: TextAlign::Center,
+            text_halign: TextHAlign::Center,
+            extra_texts: Vec::new(),
+            rainbow: None,
+            column_hue: 0.0,
+            gradient: Gradient::Trail,
+            invert: false,
+            intro_delay: Duration::ZERO,
+            depth_shading: false,
+            bold_head: true,
+            fade_in: Duration::ZERO,
+            shimmer: 1,
+            gravity: false,
+            jitter: 0,
+            ascii_shading: false,
+            mirror: false,
+            ghost_alpha: None,
+            ambient: None,
+            wake: None,
+            reveal_wpm: None,
+            cursor: false,
+            decrypt: None,
+            celebrate: false,
+            border: BorderStyle::Ascii,
+            border_color: BorderColor::default(),
+            on_frame: None,
+        }
+    }
+}
+
+/// Eine gestylte Zelle im Ausgaberaster.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: Color,
+    pub bold: bool,
+    /// Hinweis für Ausgaben ohne Farbe: Zelle gedimmt darstellen
+    /// (z. B. noch nicht eingeloggter Ziel-Text)
+    pub dim: bool,
+    /// Kopf einer Spur, so wie er zuletzt gezeichnet wurde
+    pub head: bool,
+}
+
+impl Cell {
+    /// Unbeschriebene Zelle
+    pub const EMPTY: Cell = Cell {
+        ch: ' ',
+        fg: Color::Reset,
+        bold: false,
+        dim: false,
+        head: false,
+    };
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::EMPTY
+    }
+}
+
+/// Zeilenweise abgelegtes Raster aus Zellen in Terminalgröße.
+#[derive(Debug)]
+pub struct Grid {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Grid {
+    /// Leeres Raster, z. B. als Ausgangspunkt für [`Grid::changes`].
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::EMPTY; width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn get(&self, x: u16, y: u16) -> Option<&Cell> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.cells
+            .get(y as usize * self.width as usize + x as usize)
+    }
+
+    /// Zeilen von oben nach unten
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.cells.chunks(self.width.max(1) as usize)
+    }
+
+    /// Zellen, die sich gegenüber `prev` geändert haben; bei abweichender Größe alle.
+    pub fn changes<'a>(&'a self, prev: &'a Grid) -> impl Iterator<Item = (u16, u16, &'a Cell)> {
+        let same_size = self.width == prev.width && self.height == prev.height;
+        let width = self.width.max(1) as usize;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(move |(i, cell)| !same_size || prev.cells[*i] != **cell)
+            .map(move |(i, cell)| ((i % width) as u16, (i / width) as u16, cell))
+    }
+
+    /// Setzt eine Zelle, etwa für Einblendungen über dem Regen; außerhalb bleibt es folgenlos.
+    pub fn set(&mut self, x: u16, y: u16, cell: Cell) {
+        if x < self.width && y < self.height {
+            self.cells[y as usize * self.width as usize + x as usize] = cell;
+        }
+    }
+}
+
+impl Clone for Grid {
+    fn clone(&self) -> Self {
+        Self {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.clone(),
+        }
+    }
+
+    // Speicher wiederverwenden, wenn das Vorgänger-Raster jedes Frame überschrieben wird
+    fn clone_from(&mut self, source: &Self) {
+        self.width = source.width;
+        self.height = source.height;
+        self.cells.clone_from(&source.cells);
+    }
+}
+
+/// Klartext ohne Farben, eine Zeile pro Rasterzeile.
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                f.write_str("\n")?;
+            }
+            for cell in row {
+                f.write_char(cell.ch)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Eine Regenspur; bei senkrechtem Regen eine Spalte, bei waagerechtem eine Zeile.
+#[derive(Clone)]
+struct Column {
+    /// Index quer zur Fließrichtung
+    lane: u16,
+    /// Kopfposition entlang der Fließrichtung, gezählt ab dem Eintrittsrand; `i32`, damit
+    /// Rasterlänge plus Spurlänge auch bei extrem hohen Terminals nicht überläuft
+    head: i32,
+    /// Aktuelle Verweildauer pro Schritt in Millisekunden; mit `RainConfig::gravity`
+    /// schrumpft sie im Fall, sonst bleibt sie bei `base_speed`
+    speed: u64,
+    /// Verweildauer beim Start der Spur
+    base_speed: u64,
+    phase: usize,
+    /// Seit dem letzten Schritt vergangene Zeit
+    elapsed: Duration,
+    /// Zufälliger Versatz der Verweildauer für den nächsten Schritt in Millisekunden
+    /// (`RainConfig::jitter`)
+    jitter: i32,
+    /// Farbtonverschiebung dieser Spur in Grad (`RainConfig::column_hue`)
+    hue: f32,
+    /// Spurlänge dieser Spur inklusive Kopf, neu gewürfelt bei jedem Start
+    trail_len: u16,
+}
+
+impl Column {
+    /// Neue Spur knapp vor dem Eintrittsrand.
+    fn spawn(lane: u16, config: &RainConfig, slots: usize, rng: &mut StdRng) -> Self {
+        let head = rng.gen_range(-20..0);
+        let speed = rng.gen_range(config.speed_range()); // ms pro Schritt
+        Self {
+            lane,
+            head,
+            speed,
+            base_speed: speed,
+            phase: rng.gen_range(0..slots),
+            elapsed: Duration::ZERO,
+            jitter: 0,
+            hue: if config.column_hue > 0.0 {
+                rng.gen_range(-config.column_hue..=config.column_hue)
+            } else {
+                0.0
+            },
+            trail_len: config.roll_trail_len(rng),
+        }
+    }
+}
+
+/// Zentrierter Ziel-Block samt eingeloggten Zeichen.
+struct Target {
+    lines: Vec<Vec<char>>,
+    x: u16,
+    y: u16,
+    width: u16,
+    height: u16,
+    locked: Vec<Vec<Option<char>>>,
+    /// Simulationszeit, zu der jedes Zeichen eingeloggt wurde (für `Reveal::Fade`)
+    locked_at: Vec<Vec<Duration>>,
+    reveal: Reveal,
+    /// Abstand zwischen zwei eingeloggten Zellen bei `RainConfig::reveal_wpm`
+    paced: Option<Duration>,
+    /// Einlog-Reihenfolge für `Reveal::Typewriter` und `reveal_wpm`, nächster Index darin
+    order: Vec<(usize, usize)>,
+    next: usize,
+    /// Anzahl bereits eingeloggter Zeichen
+    locked_count: usize,
+    /// Frames, die eine eingeloggte Zelle bei `RainConfig::decrypt` noch zufällige
+    /// Zeichen zeigt, und der Startwert dieses Countdowns (0 = aus)
+    scramble: Vec<Vec<u8>>,
+    decrypt: u8,
+}
+
+impl Target {
+    fn new(config: &RainConfig, spec: &TextBlock, width: u16, height: u16) -> Self {
+        // Jede Textzeile einzeln setzen, die Blöcke durch `line_spacing` Leerzeilen getrennt stapeln
+        let blocks: Vec<Vec<Vec<char>>> = spec
+            .text
+            .split('\n')
+            .map(|line| render_block(config, line.trim_end_matches('\r')))
+            .collect();
+        let block_width = blocks
+            .iter()
+            .flat_map(|block| block.iter().map(|l| l.len()))
+            .max()
+            .unwrap_or(0);
+        let mut lines: Vec<Vec<char>> = Vec::new();
+        let mut block_rows = Vec::new();
+        for (i, block) in blocks.into_iter().enumerate() {
+            if i > 0 {
+                lines.extend(std::iter::repeat_n(
+                    Vec::new(),
+                    config.line_spacing as usize,
+                ));
+            }
+            block_rows.push(lines.len()..lines.len() + block.len());
+            // Kürzere Zeilen innerhalb des Blocks mittig ausrichten
+            let width = block.iter().map(|l| l.len()).max().unwrap_or(0);
+            let pad = (block_width - width) / 2;
+            for line in block {
+                let mut padded = vec![' '; pad];
+                padded.extend(line);
+                lines.push(padded);
+            }
+        }
+        let target_height = lines.len() as u16;
+        // Auf die Terminalbreite begrenzen, damit schmale Terminals den Block abschneiden;
+        // mit Rahmen bleibt links und rechts je eine Spalte für ihn frei
+        let framed = config.style != Style::Simple
+            && config.marquee.is_none()
+            && config.border.glyphs().is_some();
+        let room = if framed {
+            width.saturating_sub(2)
+        } else {
+            width
+        };
+        let target_width = lines
+            .iter()
+            .map(|l| l.len())
+            .max()
+            .unwrap_or(0)
+            .min(room as usize) as u16;
+        for line in lines.iter_mut() {
+            line.resize(target_width as usize, ' ');
+            // Abgeschnittenes breites Zeichen am Rand nicht halb zeichnen
+            if line.last().is_some_and(|c| c.width() == Some(2)) {
+                line.pop();
+                line.push(' ');
+            }
+        }
+
+        // Ziel-Block ausrichten
+        let (left, center, right) = block_starts(target_width, width);
+        let x = match spec.halign {
+            TextHAlign::Left => left,
+            TextHAlign::Center => center,
+            TextHAlign::Right => right,
+        };
+        let (top, center, bottom) = block_starts(target_height, height);
+        let y = match spec.align {
+            TextAlign::Top => top,
+            TextAlign::Center => center,
+            TextAlign::Bottom => bottom,
+            TextAlign::Row(row) => row.clamp(top, bottom),
+        };
+
+        // Lesereihenfolge für `Reveal::Typewriter` und `reveal_wpm`: Textzeile für Textzeile, darin
+        // spaltenweise von links nach rechts, damit Buchstabe für Buchstabe erscheint
+        let mut order = Vec::new();
+        for rows in block_rows {
+            for col in 0..target_width as usize {
+                for (i, line) in lines[rows.clone()].iter().enumerate() {
+                    if lockable(line[col]) {
+                        order.push((rows.start + i, col));
+                    }
+                }
+            }
+        }
+
+        let mut target = Self {
+            lines,
+            x,
+            y,
+            width: target_width,
+            height: target_height,
+            // Für jedes Zeichen im Ziel-String merken wir, ob es schon „eingeloggt“ ist
+            locked: vec![vec![None; target_width as usize]; target_height as usize],
+            locked_at: vec![vec![Duration::ZERO; target_width as usize]; target_height as usize],
+            reveal: config.reveal,
+            paced: config.reveal_wpm.map(|wpm| {
+                // Ein Wort = fünf Zeichen; die Zeit je Zeichen wird auf dessen Glyphenzellen verteilt
+                let chars = spec.text.chars().filter(|c| !c.is_whitespace()).count();
+                let per_char = Duration::from_secs_f64(60.0 / (wpm.max(1) as f64 * 5.0));
+                per_char * chars.max(1) as u32 / order.len().max(1) as u32
+            }),
+            order,
+            next: 0,
+            locked_count: 0,
+            scramble: vec![vec![0; target_width as usize]; target_height as usize],
+            decrypt: config.decrypt.unwrap_or(0),
+        };
+        // Laufschrift leuchtet von Anfang an vollständig
+        if config.marquee.is_some() {
+            target.paced = None;
+            for &(row, col) in &target.order {
+                target.locked[row][col] = Some(target.lines[row][col]);
+            }
+            target.next = target.order.len();
+            target.locked_count = target.order.len();
+        }
+        target
+    }
+
+    /// Loggt bei `reveal_wpm` alle Zellen ein, die bis zur Zeit `elapsed` seit Beginn
+    /// des Einloggens fällig sind, in Lesereihenfolge.
+    fn lock_paced(&mut self, elapsed: Duration, now: Duration) {
+        let Some(interval) = self.paced else {
+            return;
+        };
+        while let Some(&(row, col)) = self.order.get(self.next) {
+            if interval * (self.next as u32 + 1) > elapsed {
+                break;
+            }
+            self.next += 1;
+            self.lock(row, col, now);
+        }
+    }
+
+    /// Loggt die Blockzelle `(row, col)` ein und startet ihren Entschlüsselungs-Countdown.
+    fn lock(&mut self, row: usize, col: usize, now: Duration) {
+        self.locked[row][col] = Some(self.lines[row][col]);
+        self.locked_at[row][col] = now;
+        self.locked_count += 1;
+        self.scramble[row][col] = self.decrypt;
+    }
+
+    /// Zählt den Entschlüsselungs-Countdown aller Zellen um einen Frame herunter.
+    fn tick_scramble(&mut self) {
+        if self.decrypt > 0 {
+            for frames in self.scramble.iter_mut().flatten() {
+                *frames = frames.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Trifft ein Kopf zum Zeitpunkt `now` an Rasterposition `(x, y)` auf ein noch
+    /// offenes Zielzeichen, wird es eingeloggt – bei `Typewriter` stattdessen das
+    /// nächste in Lesereihenfolge.
+    fn lock_at(&mut self, x: u16, y: u16, now: Duration) {
+        if x < self.x || y < self.y {
+            return;
+        }
+        let (row, col) = ((y - self.y) as usize, (x - self.x) as usize);
+        let Some(target_ch) = self.lines.get(row).and_then(|line| line.get(col)).copied() else {
+            return;
+        };
+        if !lockable(target_ch) || self.locked[row][col].is_some() {
+            return;
+        }
+        let (row, col) = match self.reveal {
+            Reveal::Typewriter => match self.order.get(self.next) {
+                Some(&next) => {
+                    self.next += 1;
+                    next
+                }
+                None => return,
+            },
+            Reveal::Instant | Reveal::Fade => (row, col),
+        };
+        self.lock(row, col, now);
+    }
+
+    /// Rasterposition `(x, y)` liegt auf einem Zeichen des Ziel-Texts (nicht auf Leerraum).
+    fn is_glyph(&self, x: u16, y: u16) -> bool {
+        if x < self.x || y < self.y {
+            return false;
+        }
+        let (row, col) = ((y - self.y) as usize, (x - self.x) as usize);
+        self.lines
+            .get(row)
+            .and_then(|line| line.get(col))
+            .is_some_and(|ch| lockable(*ch))
+    }
+
+    /// Bildschirmspalte der Blockspalte `col` bei Laufschrift-Versatz `shift`: nach links
+    /// verschoben, was links hinausläuft, kommt rechts wieder herein.
+    fn marquee_x(&self, col: usize, shift: u16, width: u16) -> u16 {
+        (self.x + col as u16 + width - shift) % width
+    }
+
+    /// Alle einloggbaren Zeichen sind eingeloggt und entschlüsselt.
+    fn is_complete(&self) -> bool {
+        self.locked_count == self.order.len()
+            && (self.decrypt == 0 || self.scramble.iter().flatten().all(|&frames| frames == 0))
+    }
+}
+
+/// Leerzeichen und die zweite Hälfte breiter Zeichen werden nie eingeloggt.
+fn lockable(ch: char) -> bool {
+    ch != ' ' && ch != WIDE_CONT
+}
+
+/// Setzt eine einzelne Textzeile in FIGlet- oder integrierter Bitmap-Schrift.
+fn render_block(config: &RainConfig, text: &str) -> Vec<Vec<char>> {
+    let (rows, font_height) = match &config.figlet {
+        Some(figlet) => (figlet.render(text), figlet.height()),
+        None => (
+            render_text_spaced(text, config.font, config.letter_spacing as usize),
+            config.font.height(),
+        ),
+    };
+    let mut block: Vec<Vec<char>> = rows.iter().map(|l| display_cells(l)).collect();
+    // Leerer Text ergibt keine Zeilen, der Block behält trotzdem die Schrifthöhe
+    block.resize(font_height, Vec::new());
+    block
+}
+
+/// Zerlegt eine Zeile in Terminalzellen: breite Zeichen belegen zwei Spalten,
+/// Zeichen ohne Breite (Joiner, Kombinationszeichen) keine.
+fn display_cells(line: &str) -> Vec<char> {
+    let mut cells = Vec::new();
+    for ch in line.chars() {
+        match ch.width().unwrap_or(0) {
+            0 => {}
+            1 => cells.push(ch),
+            _ => {
+                cells.push(ch);
+                cells.push(WIDE_CONT);
+            }
+        }
+    }
+    cells
+}
+
+/// Frames zwischen zwei Verschiebungen für `scroll_speed` 0–10; 0 schiebt praktisch nie.
+fn scroll_interval(speed: u8) -> u16 {
+    if speed == 0 {
+        u16::MAX
+    } else {
+        11 - speed.min(10) as u16
+    }
+}
+
+/// Farbton des Regenbogen-Modus in Grad; eine volle Umdrehung dauert `period` Frames.
+///
+/// ```
+/// use matrix::rainbow_hue;
+///
+/// let half = rainbow_hue(60, 120) - rainbow_hue(0, 120);
+/// assert!((half - 180.0).abs() < 1e-3);
+/// assert_eq!(rainbow_hue(120, 120), 0.0);
+/// ```
+pub fn rainbow_hue(frame: usize, period: u32) -> f32 {
+    let period = period.max(1) as usize;
+    (frame % period) as f32 / period as f32 * 360.0
+}
+
+/// Zeichen von hell nach dicht für [`RainConfig::ascii_shading`]
+pub const SHADING_RAMP: &str = " .:-=+*#%@";
+
+/// Zeichen der [`SHADING_RAMP`] für die Helligkeit `t` (0.0 = leer, 1.0 = dichtestes
+/// Zeichen), auf die nächste Stufe gerundet.
+///
+/// ```
+/// use matrix::shade_char;
+///
+/// assert_eq!(shade_char(1.0), '@');
+/// assert_eq!(shade_char(0.5), '+');
+/// assert_eq!(shade_char(0.1), '.');
+/// assert_eq!(shade_char(0.0), ' ');
+/// ```
+pub fn shade_char(t: f32) -> char {
+    let ramp = SHADING_RAMP.as_bytes();
+    let i = (t.clamp(0.0, 1.0) * (ramp.len() - 1) as f32).round() as usize;
+    ramp[i] as char
+}
+
+/// Farbtabellen über die Spurlänge, eine je möglicher Länge (Index = Länge, nicht
+/// vorkommende Längen bleiben leer): der Kopf bekommt das Ende des Verlaufs, mit
+/// `reverse_gradient` den Anfang.
+fn trail_colors(config: &RainConfig) -> Vec<Vec<Color>> {
+    let range = config.trail_range();
+    let colorset = config.rain_colors();
+    (0..=*range.end())
+        .map(|trail_len| {
+            if !range.contains(&trail_len) {
+                return Vec::new();
+            }
+            (0..trail_len)
+                .map(|offset| {
+                    let t = offset as f32 / (trail_len - 1).max(1) as f32;
+                    let t = if config.reverse_gradient { t } else { 1.0 - t };
+                    let color = colorset.gradient_color(t);
+                    glow(color, config.glow, offset as usize)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Farbtabelle nach Bildschirmzeile ([`Gradient::Screen`]) bzw. -spalte
+/// ([`Gradient::Column`]); bei [`Gradient::Trail`] leer.
+fn screen_colors(config: &RainConfig, width: u16, height: u16) -> Vec<Color> {
+    let len = match config.gradient {
+        Gradient::Trail => return Vec::new(),
+        Gradient::Screen => height,
+        Gradient::Column => width,
+    };
+    let colorset = config.rain_colors();
+    (0..len)
+        .map(|pos| {
+            let t = pos as f32 / len.saturating_sub(1).max(1) as f32;
+            colorset.gradient_color(t)
+        })
+        .collect()
+}
+
+/// Leerer Nachleucht-Puffer je Zelle; ohne `wake` ganz leer.
+fn wake_buffer(config: &RainConfig, width: u16, height: u16) -> Vec<f32> {
+    match config.wake {
+        Some(_) => vec![0.0; width as usize * height as usize],
+        None => Vec::new(),
+    }
+}
+
+/// Kumulierte Gewichte je Zeichen von `charset` für `charset_weights`; leer ohne
+/// Gewichte oder wenn alle Gewichte 0 sind.
+fn cumulative_weights(config: &RainConfig, charset: &[char]) -> Vec<usize> {
+    if config.charset_weights.is_empty() {
+        return Vec::new();
+    }
+    let mut total = 0;
+    let cumulative: Vec<usize> = charset
+        .iter()
+        .map(|ch| {
+            // Bei doppelt genannten Zeichen gilt die letzte Angabe
+            let weight = config
+                .charset_weights
+                .iter()
+                .rev()
+                .find(|(c, _)| c == ch)
+                .map_or(1, |&(_, weight)| weight);
+            total += weight as usize;
+            total
+        })
+        .collect();
+    if total == 0 { Vec::new() } else { cumulative }
+}
+
+/// Plätze der Zeichenrotation: die Zeichenanzahl, gewichtet die Summe der Gewichte.
+fn glyph_slots(charset: &[char], cumulative: &[usize]) -> usize {
+    cumulative.last().copied().unwrap_or(charset.len())
+}
+
+/// Zeichen auf Platz `slot` der Rotation; gewichtet belegt jedes Zeichen so viele
+/// aufeinanderfolgende Plätze, wie es wiegt, und wird über die kumulierten Gewichte gefunden.
+fn glyph_at(charset: &[char], cumulative: &[usize], slot: usize) -> char {
+    match cumulative.last() {
+        Some(&total) => charset[cumulative.partition_point(|&c| c <= slot % total)],
+        None => charset[slot % charset.len()],
+    }
+}
+
+/// Zufällige, feste Zeichen für die Hintergrundschicht; ohne `ambient` leer und ohne Zufallszug.
+fn ambient_chars(
+    config: &RainConfig,
+    charset: &[char],
+    width: u16,
+    height: u16,
+    rng: &mut StdRng,
+) -> Vec<char> {
+    if config.ambient.is_none() {
+        return Vec::new();
+    }
+    // Breite Zeichen würden in die Nachbarzelle ragen
+    let narrow: Vec<char> = charset
+        .iter()
+        .copied()
+        .filter(|c| c.width() == Some(1))
+        .collect();
+    let pool = if narrow.is_empty() {
+        &['.'][..]
+    } else {
+        &narrow
+    };
+    (0..width as usize * height as usize)
+        .map(|_| pool[rng.gen_range(0..pool.len())])
+        .collect()
+}
+
+/// Farbtabelle für [`Style::Aged`], nach Alter in Spurschritten (0 = frisch).
+fn age_colors(config: &RainConfig) -> Vec<Color> {
+    let max_age = config.trail_len.max(1) as usize;
+    let colorset = config.rain_colors();
+    (0..max_age)
+        .map(|age| {
+            let t = age as f32 / (max_age - 1).max(1) as f32;
+            // Mit `trail_gradient` beginnen frische Zellen in der Kopffarbe
+            let t = if config.trail_gradient.is_some() {
+                1.0 - t
+            } else {
+                t
+            };
+            glow(colorset.gradient_color(t), config.glow, age)
+        })
+        .collect()
+}
+
+/// Tiefe einer Spur aus ihrer Geschwindigkeit: 1.0 für die schnellste, -1.0 für die
+/// langsamste Spur der Spanne, 0.0 wenn alle gleich schnell sind.
+fn speed_depth(speed: u64, range: &std::ops::RangeInclusive<u64>) -> f32 {
+    let (lo, hi) = (*range.start(), *range.end());
+    if hi == lo {
+        return 0.0;
+    }
+    1.0 - 2.0 * (speed.clamp(lo, hi) - lo) as f32 / (hi - lo) as f32
+}
+
+/// Hellt den Kopf (`offset` 0) um `strength` Richtung Weiß auf, die zwei Zellen
+/// dahinter mit halber bzw. viertel Stärke.
+fn glow(color: Color, strength: f32, offset: usize) -> Color {
+    const FALLOFF: [f32; 3] = [1.0, 0.5, 0.25];
+    match FALLOFF.get(offset) {
+        Some(f) if strength > 0.0 => blend_color(color, Color::White, strength * f),
+        _ => color,
+    }
+}
+
+/// Der Regen spart Rasterposition `(x, y)` aus: den Rahmen samt Ziel-Text, bei
+/// `invert` alles außer den Glyphen. Ohne Ziel-Text (`targeted` aus) regnet es überall.
+fn rain_blocked(
+    targeted: bool,
+    invert: bool,
+    targets: &[Target],
+    borders: &[Border],
+    x: u16,
+    y: u16,
+) -> bool {
+    if !targeted {
+        return false;
+    }
+    if invert {
+        !targets.iter().any(|t| t.is_glyph(x, y))
+    } else {
+        in_border(borders, x, y)
+    }
+}
+
+/// `(x, y)` liegt in einem der Rahmen samt Ziel-Block.
+fn in_border(borders: &[Border], x: u16, y: u16) -> bool {
+    borders.iter().any(|b| b.contains(x, y))
+}
+
+/// Ein Ziel-Block je Ziel-Text, ausgerichtet in einem Raster von `width` x `height`.
+fn build_targets(config: &RainConfig, width: u16, height: u16) -> Vec<Target> {
+    config
+        .text_blocks()
+        .map(|spec| Target::new(config, &spec, width, height))
+        .collect()
+}
+
+/// Rahmen um jeden Ziel-Block; keine bei [`Style::Simple`] und bei `marquee`.
+fn build_borders(config: &RainConfig, targets: &[Target], width: u16, height: u16) -> Vec<Border> {
+    if config.style == Style::Simple || config.marquee.is_some() {
+        return Vec::new();
+    }
+    targets
+        .iter()
+        .map(|target| Border::around(target, width, height))
+        .collect()
+}
+
+/// Rahmen um den Ziel-Block (inklusive Koordinaten).
+struct Border {
+    x0: u16,
+    x1: u16,
+    y0: u16,
+    y1: u16,
+}
+
+impl Border {
+    fn around(target: &Target, width: u16, height: u16) -> Self {
+        Self {
+            x0: target.x.saturating_sub(1),
+            x1: (target.x + target.width).min(width.saturating_sub(1)),
+            y0: target.y.saturating_sub(1),
+            y1: (target.y + target.height).min(height.saturating_sub(1)),
+        }
+    }
+
+    fn contains(&self, x: u16, y: u16) -> bool {
+        x >= self.x0 && x <= self.x1 && y >= self.y0 && y <= self.y1
+    }
+
+    fn overlaps(&self, other: &Border) -> bool {
+        self.x0 <= other.x1 && other.x0 <= self.x1 && self.y0 <= other.y1 && other.y0 <= self.y1
+    }
+}
+
+/// Zustand des digitalen Regens für eine feste Rastergröße.
+pub struct RainState {
+    config: RainConfig,
+    grid: Grid,
+    columns: Vec<Column>,
+    /// Ziel-Blöcke: zuerst `RainConfig::text`, dann `RainConfig::extra_texts`
+    targets: Vec<Target>,
+    /// Rahmen um jeden Ziel-Block; leer bei [`Style::Simple`]
+    borders: Vec<Border>,
+    charset: Vec<char>,
+    /// Kumulierte Gewichte je Zeichen von `charset`; leer bei Gleichverteilung
+    cumulative: Vec<usize>,
+    frame: usize,
+    /// Bisherige Aufrufe von `step`, auch während der Feier; Frame-Nummer für `on_frame`
+    steps: usize,
+    /// Versatz der Zeichenrotation, rückt alle `RainConfig::shimmer` Frames weiter
+    char_offset: usize,
+    bg_shift: u16,
+    bg_tick: u16,
+    scroll_interval: u16,
+    /// Versatz der Laufschrift nach links, in Spalten modulo Rasterbreite
+    marquee_shift: u16,
+    rng: StdRng,
+    /// Laufzeit der Simulation und Zeitpunkt des letzten Zeichnens je Zelle (für `density`)
+    clock: Duration,
+    painted: Vec<Duration>,
+    /// Farbe je Spur-Offset (0 = Kopf); der Verlauf ist konstant und wird nur beim
+    /// Wechsel des Farbsets neu berechnet
+    trail_colors: Vec<Vec<Color>>,
+    age_colors: Vec<Color>,
+    /// Farbe je Bildschirmzeile bzw. -spalte bei [`Gradient::Screen`]/[`Gradient::Column`]
+    screen_colors: Vec<Color>,
+    /// Feste Zeichen der Hintergrundschicht je Zelle; leer ohne `RainConfig::ambient`
+    ambient: Vec<char>,
+    /// Nachleuchten je Zelle (1.0 direkt nach dem Kopf); leer ohne `RainConfig::wake`
+    wake: Vec<f32>,
+    /// Simulationszeit, zu der der Ziel-Text bei `RainConfig::celebrate` vollständig wurde
+    celebrated_at: Option<Duration>,
+    /// Aktuelle Funken samt der Zelle, die sie verdecken
+    sparkles: Vec<(u16, u16, Cell)>,
+}
+
+impl RainState {
+    /// Simulation mit Standard-Einstellungen.
+    pub fn new(width: u16, height: u16) -> Self {
+        Self::with_config(RainConfig::default(), width, height)
+    }
+
+    pub fn with_config(config: RainConfig, width: u16, height: u16) -> Self {
+        let targets = build_targets(&config, width, height);
+        let borders = build_borders(&config, &targets, width, height);
+        let charset = if config.charset.is_empty() {
+            Charset::default().chars()
+        } else {
+            config.charset.clone()
+        };
+        let cumulative = cumulative_weights(&config, &charset);
+        let slots = glyph_slots(&charset, &cumulative);
+        let trail_colors = trail_colors(&config);
+        let age_colors = age_colors(&config);
+        let screen_colors = screen_colors(&config, width, height);
+        let scroll_interval = scroll_interval(config.scroll_speed);
+
+        // Spuren initialisieren: pro Spalte bei senkrechtem, pro Zeile bei waagerechtem Regen
+        let lanes = if config.direction.is_vertical() {
+            width
+        } else {
+            height
+        };
+        let mut rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let columns: Vec<Column> = (0..lanes)
+            .map(|lane| Column::spawn(lane, &config, slots, &mut rng))
+            .collect();
+        let ambient = ambient_chars(&config, &charset, width, height, &mut rng);
+        let wake = wake_buffer(&config, width, height);
+
+        let mut state = Self {
+            config,
+            grid: Grid::new(width, height),
+            columns,
+            targets,
+            borders,
+            charset,
+            cumulative,
+            frame: 0,
+            steps: 0,
+            char_offset: 0,
+            bg_shift: 0,
+            bg_tick: 0,
+            scroll_interval,
+            marquee_shift: 0,
+            rng,
+            clock: Duration::ZERO,
+            painted: vec![Duration::ZERO; width as usize * height as usize],
+            trail_colors,
+            age_colors,
+            screen_colors,
+            ambient,
+            wake,
+            celebrated_at: None,
+            sparkles: Vec::new(),
+        };
+        // Der Rahmen ändert sich nach dem Platzieren nicht mehr und wird vom Regen
+        // ausgespart, also nur einmal zeichnen
+        state.draw_border();
+        state
+    }
+
+    pub fn config(&self) -> &RainConfig {
+        &self.config
+    }
+
+    /// Passt die Simulation an eine neue Terminalgröße an: Raster und Rahmen werden
+    /// neu aufgebaut und der Ziel-Text neu zentriert; bereits eingeloggte Zeichen
+    /// bleiben erhalten, solange der Text unverändert passt. Spuren jenseits der neuen
+    /// Breite fallen weg, neue Spuren kommen hinzu.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        if (width, height) == (self.grid.width, self.grid.height) {
+            return;
+        }
+        let mut targets = build_targets(&self.config, width, height);
+        for (target, old) in targets.iter_mut().zip(&mut self.targets) {
+            if target.lines == old.lines {
+                target.locked = std::mem::take(&mut old.locked);
+                target.locked_at = std::mem::take(&mut old.locked_at);
+                target.scramble = std::mem::take(&mut old.scramble);
+                target.next = old.next;
+                target.locked_count = old.locked_count;
+            }
+        }
+        self.borders = build_borders(&self.config, &targets, width, height);
+        self.targets = targets;
+
+        let lanes = if self.config.direction.is_vertical() {
+            width
+        } else {
+            height
+        };
+        self.columns.retain(|col| col.lane < lanes);
+        for lane in self.columns.len() as u16..lanes {
+            let slots = glyph_slots(&self.charset, &self.cumulative);
+            let col = Column::spawn(lane, &self.config, slots, &mut self.rng);
+            self.columns.push(col);
+        }
+        self.bg_shift %= lanes.max(1);
+        self.marquee_shift %= width.max(1);
+
+        self.grid = Grid::new(width, height);
+        self.sparkles.clear();
+        self.painted = vec![self.clock; width as usize * height as usize];
+        self.screen_colors = screen_colors(&self.config, width, height);
+        self.ambient = ambient_chars(&self.config, &self.charset, width, height, &mut self.rng);
+        self.wake = wake_buffer(&self.config, width, height);
+        self.draw_border();
+    }
+
+    /// Tauscht das Farbset zur Laufzeit; wirkt ab dem nächsten Schritt.
+    pub fn set_colorset(&mut self, colorset: ColorSet) {
+        self.config.colorset = colorset;
+        self.trail_colors = trail_colors(&self.config);
+        self.age_colors = age_colors(&self.config);
+        self.screen_colors = screen_colors(&self.config, self.grid.width, self.grid.height);
+        if self.config.border_color == BorderColor::Auto {
+            self.draw_border();
+        }
+    }
+
+    /// Ändert die Verschiebungsgeschwindigkeit zur Laufzeit (auf 0–10 begrenzt).
+    pub fn set_scroll_speed(&mut self, speed: u8) {
+        self.config.scroll_speed = speed.min(10);
+        self.scroll_interval = scroll_interval(self.config.scroll_speed);
+        self.bg_tick = 0;
+    }
+
+    /// Aktueller Versatz der Laufschrift nach links in Spalten; läuft an der
+    /// Rasterbreite auf 0 zurück. Ohne [`RainConfig::marquee`] immer 0.
+    ///
+    /// ```
+    /// use matrix::{RainConfig, RainState};
+    /// use std::time::Duration;
+    ///
+    /// // Geschwindigkeit 10 schiebt jedes Frame um eine Spalte
+    /// let config = RainConfig { marquee: Some(10), seed: Some(1), ..RainConfig::default() };
+    /// let mut state = RainState::with_config(config, 20, 9);
+    /// for _ in 0..3 {
+    ///     state.step(Duration::from_millis(16));
+    /// }
+    /// assert_eq!(state.marquee_offset(), 3);
+    /// for _ in 3..20 {
+    ///     state.step(Duration::from_millis(16));
+    /// }
+    /// assert_eq!(state.marquee_offset(), 0);
+    /// ```
+    pub fn marquee_offset(&self) -> u16 {
+        self.marquee_shift
+    }
+
+    /// Startet die Spuren um Rasterposition `(x, y)` (bis [`BURST_RADIUS`] Spuren zu
+    /// jeder Seite) neu mit dem Kopf knapp vor dieser Stelle und zufälligen
+    /// Geschwindigkeiten, etwa für einen Mausklick.
+    pub fn burst(&mut self, x: u16, y: u16) {
+        let (width, height) = (self.grid.width, self.grid.height);
+        if x >= width || y >= height {
+            return;
+        }
+        let direction = self.config.direction;
+        let (lanes, lane, pos) = match direction {
+            Direction::Down => (width, x, y),
+            Direction::Up => (width, x, height - 1 - y),
+            Direction::Right => (height, y, x),
+            Direction::Left => (height, y, width - 1 - x),
+        };
+        let (lanes, radius) = (lanes as i32, BURST_RADIUS as i32);
+        for d in -radius..=radius {
+            let screen_lane = lane as i32 + d;
+            if !(0..lanes).contains(&screen_lane) {
+                continue;
+            }
+            // `bg_shift` zurückrechnen, um die Spur unter dieser Rasterspalte zu finden
+            let col_lane = (screen_lane - self.bg_shift as i32).rem_euclid(lanes) as u16;
+            let Some(col) = self.columns.iter_mut().find(|c| c.lane == col_lane) else {
+                continue;
+            };
+            col.head = pos as i32 - self.rng.gen_range(0..=radius);
+            col.speed = self.rng.gen_range(self.config.speed_range());
+            col.base_speed = col.speed;
+            col.elapsed = Duration::ZERO;
+        }
+    }
+
+    /// Jedes sichtbare Zeichen des Ziel-Texts ist eingeloggt; bei [`Style::Simple`]
+    /// ohne Ziel-Text nie.
+    pub fn is_complete(&self) -> bool {
+        self.config.style != Style::Simple && self.targets.iter().all(Target::is_complete)
+    }
+
+    /// Zwei Ziel-Blöcke überlappen samt Rahmen in der aktuellen Rastergröße; dann
+    /// zeichnet der spätere über den früheren.
+    pub fn targets_overlap(&self) -> bool {
+        if self.config.style == Style::Simple {
+            return false;
+        }
+        let boxes: Vec<Border> = self
+            .targets
+            .iter()
+            .map(|t| Border::around(t, self.grid.width, self.grid.height))
+            .collect();
+        boxes
+            .iter()
+            .enumerate()
+            .any(|(i, a)| boxes[i + 1..].iter().any(|b| a.overlaps(b)))
+    }
+
+    /// `(x, y)` gehört zu einem Ziel-Block samt Rahmen, bei `marquee` zu dessen Zeilen;
+    /// Einblendungen über dem Raster lassen solche Zellen frei.
+    pub fn in_text_area(&self, x: u16, y: u16) -> bool {
+        if self.config.style == Style::Simple {
+            return false;
+        }
+        let marquee = self.config.marquee.is_some();
+        in_border(&self.borders, x, y)
+            || self.targets.iter().any(|t| {
+                (t.y..t.y + t.height).contains(&y) && (marquee || (t.x..t.x + t.width).contains(&x))
+            })
+    }
+
+    /// Spuren, deren Kopf oder Trail gerade im Raster liegt.
+    pub fn active_columns(&self) -> usize {
+        let flow_len = if self.config.direction.is_vertical() {
+            self.grid.height
+        } else {
+            self.grid.width
+        } as i32;
+        self.columns
+            .iter()
+            .filter(|col| col.head >= 0 && col.head < flow_len + col.trail_len as i32)
+            .count()
+    }
+
+    /// Der Ziel-Text ist vollständig und die Feier von [`RainConfig::celebrate`] läuft noch.
+    pub fn is_celebrating(&self) -> bool {
+        self.celebrated_at
+            .is_some_and(|at| self.clock.saturating_sub(at) < CELEBRATION)
+    }
+
+    /// Bisher eingeloggte Zeichen der Ziel-Blöcke als Klartext, eine Zeile pro Blockzeile
+    /// und eine Leerzeile zwischen den Blöcken; noch offene Zeichen bleiben Leerzeichen,
+    /// Zeilenenden ohne Leerraum. Bei [`Style::Simple`] leer.
+    pub fn revealed_text(&self) -> String {
+        if self.config.style == Style::Simple {
+            return String::new();
+        }
+        let mut out = String::new();
+        for (i, target) in self.targets.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            for row in &target.locked {
+                let line: String = row
+                    .iter()
+                    .filter(|ch| **ch != Some(WIDE_CONT))
+                    .map(|ch| ch.unwrap_or(' '))
+                    .collect();
+                out.push_str(line.trim_end());
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Beschriebene Zellen des zuletzt berechneten Frames als `(x, y, Zeichen, Farbe, Kopf)`,
+    /// zeilenweise von oben links; etwa zum Zeichnen in andere Oberflächen oder Bilder.
+    pub fn frame_cells(&self) -> impl Iterator<Item = (u16, u16, char, Color, bool)> + '_ {
+        let width = self.grid.width.max(1) as usize;
+        self.grid
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| !cell.is_empty())
+            .map(move |(i, cell)| {
+                let (x, y) = ((i % width) as u16, (i / width) as u16);
+                (x, y, cell.ch, cell.fg, cell.head)
+            })
+    }
+
+    /// Raster des zuletzt berechneten Frames.
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// Rückt die Simulation um `dt` weiter und liefert das neue Raster; danach läuft
+    /// `RainConfig::on_frame`.
+    pub fn step(&mut self, dt: Duration) -> &Grid {
+        self.advance(dt);
+        if let Some(FrameHook(hook)) = self.config.on_frame.clone() {
+            (hook.borrow_mut())(self, self.steps);
+        }
+        self.steps += 1;
+        &self.grid
+    }
+
+    /// Ein Simulationsschritt ohne Rückruf.
+    ///
+    /// `bg_shift` verschiebt die Spuren quer zur Fließrichtung: bei `Down`/`Up`
+    /// wandern die Spalten nach rechts, bei `Left`/`Right` die Zeilen nach unten.
+    fn advance(&mut self, dt: Duration) {
+        let (width, height) = (self.grid.width, self.grid.height);
+        let direction = self.config.direction;
+        let (lanes, flow_len) = if direction.is_vertical() {
+            (width, height)
+        } else {
+            (height, width)
+        };
+        let flow_len_i32 = flow_len as i32;
+
+        let was_intro = self.clock < self.config.intro_delay;
+        self.clock += dt;
+        // Vorlauf vorbei: Ziel-Bereich vom Regen befreien und den Rahmen zeichnen
+        let intro = self.clock < self.config.intro_delay;
+        if was_intro && !intro {
+            self.end_intro();
+        }
+
+        // Feier: der Regen steht still, nur die Funken wechseln
+        self.clear_sparkles();
+        if self.is_celebrating() {
+            self.sparkle();
+            return;
+        }
+
+        // Frame-Tick für durchlaufende Zeichenrotation; Zellen, die erst in diesem Schritt
+        // einloggen, zeigen danach ihre vollen `decrypt`-Frames
+        self.frame = self.frame.wrapping_add(1);
+        for target in &mut self.targets {
+            target.tick_scramble();
+        }
+        let shimmer = self.config.shimmer as usize;
+        if shimmer > 0 && self.frame.is_multiple_of(shimmer) {
+            self.char_offset = self.char_offset.wrapping_add(1);
+        }
+        self.bg_tick = self.bg_tick.wrapping_add(1);
+        if self.bg_tick.is_multiple_of(self.scroll_interval) {
+            self.bg_shift = (self.bg_shift + 1) % lanes.max(1);
+        }
+        if let Some(speed) = self.config.marquee
+            && self.bg_tick.is_multiple_of(scroll_interval(speed))
+        {
+            self.clear_marquee();
+            self.marquee_shift = (self.marquee_shift + 1) % width.max(1);
+        }
+
+        // Regenbogen: Verlauf jedes Frame aus dem umlaufenden Farbton neu ableiten
+        if let Some(period) = self.config.rainbow {
+            let hue = rainbow_hue(self.frame, period);
+            let ramp = vec![
+                hsv_to_color(hue - 40.0, 1.0, 0.35),
+                hsv_to_color(hue, 1.0, 1.0),
+            ];
+            self.set_colorset(self.config.colorset.with_colors(ramp));
+        }
+
+        // Nachleuchten klingt jedes Frame ab
+        if let Some(retain) = self.config.wake {
+            let retain = retain.clamp(0.0, 1.0);
+            self.wake.iter_mut().for_each(|glow| *glow *= retain);
+        }
+
+        // Regen aktualisieren; `slots` sind die Plätze der Zeichenrotation
+        let slots = glyph_slots(&self.charset, &self.cumulative);
+        let density = self.config.density.clamp(0.0, 1.0) as f64;
+        let style = self.config.style;
+        let flicker = self.config.flicker.clamp(0.0, 1.0) as f64;
+        // Im Vorlauf verhält sich der Regen wie bei `Simple`
+        let targeted = style != Style::Simple && !intro;
+        // Die Laufschrift liegt über dem Regen, statt ihn zu formen
+        let invert = self.config.invert && targeted && self.config.marquee.is_none();
+        // Einblenden: Anteil des Hintergrunds, der zum Start hin zunimmt
+        let fade = if self.clock < self.config.fade_in {
+            1.0 - self.clock.as_secs_f32() / self.config.fade_in.as_secs_f32()
+        } else {
+            0.0
+        };
+        let jitter = self.config.jitter as i32;
+        let mirror = self.config.mirror;
+        let half = width.div_ceil(2);
+        // Tiefenwirkung: Zielfarben für schnelle (hell) und langsame Spuren (Hintergrund)
+        let depth_shading = self.config.depth_shading.then(|| {
+            (
+                self.config.speed_range(),
+                self.config.rain_colors().gradient_color(1.0),
+                self.config.colorset.background(),
+            )
+        });
+        for col in self.columns.iter_mut() {
+            col.elapsed += dt;
+            let speed = col.speed.saturating_add_signed(col.jitter as i64).max(1);
+            if col.elapsed < Duration::from_millis(speed) {
+                continue;
+            }
+            col.elapsed = Duration::ZERO;
+            if jitter > 0 {
+                col.jitter = self.rng.gen_range(-jitter..=jitter);
+            }
+            col.phase = (col.phase + 1) % slots;
+
+            // Kopf einen Schritt in Fließrichtung
+            // wartende Spuren bleiben hinter dem Rand stehen
+            let trail_len = col.trail_len as i32;
+            col.head = (col.head + 1).min(flow_len_i32 + trail_len);
+            // Schwerkraft: im sichtbaren Fall wird jeder Schritt um `GRAVITY` kürzer
+            if self.config.gravity && col.head >= 0 {
+                col.speed = ((col.speed as f32 * GRAVITY).round() as u64).clamp(1, col.speed);
+            }
+
+            // Bei `Aged` zeichnet nur der Kopf, den Verlauf übernimmt `age_cells`
+            let drawn = if style == Style::Aged { 1 } else { trail_len };
+
+            let lane = (col.lane + self.bg_shift) % lanes.max(1);
+            for offset in 0..drawn {
+                let pos = col.head - offset;
+                if pos < 0 || pos >= flow_len_i32 {
+                    continue;
+                }
+                let (x, y) = direction.to_xy(lane, pos as u16, flow_len);
+                // Mit `mirror` zeichnet nur die linke Hälfte, die rechte entsteht als Spiegelbild
+                if (mirror && x >= half)
+                    || rain_blocked(targeted, invert, &self.targets, &self.borders, x, y)
+                {
+                    continue;
+                }
+
+                // Helligkeit entlang des Trails bzw. nach Bildschirmposition aus der
+                // vorberechneten Tabelle
+                let mut color = match self.config.gradient {
+                    Gradient::Trail => self.trail_colors[col.trail_len as usize][offset as usize],
+                    Gradient::Screen => glow(
+                        self.screen_colors[y as usize],
+                        self.config.glow,
+                        offset as usize,
+                    ),
+                    Gradient::Column => glow(
+                        self.screen_colors[x as usize],
+                        self.config.glow,
+                        offset as usize,
+                    ),
+                };
+                if col.hue != 0.0 {
+                    color = rotate_hue(color, col.hue);
+                }
+                if let Some((range, bright, dark)) = &depth_shading {
+                    let depth = speed_depth(col.base_speed, range);
+                    let toward = if depth > 0.0 { *bright } else { *dark };
+                    color = blend_color(color, toward, depth.abs() * DEPTH_SHADING);
+                }
+                if fade > 0.0 {
+                    color = blend_color(color, self.config.colorset.background(), fade);
+                }
+                let i = y as usize * width as usize + x as usize;
+                if offset > 0
+                    && let Some(&glow) = self.wake.get(i)
+                {
+                    color = blend_color(color, Color::White, glow * WAKE_GLOW);
+                }
+                if offset == 0 && !self.config.bold_head {
+                    color = blend_color(color, Color::White, HEAD_BRIGHTEN);
+                }
+
+                // Kopf heller/fetter; mit `flicker` springt die Zelle auf ein zufälliges Zeichen
+                let ch = if self.config.ascii_shading {
+                    // Dichte statt Zeichensatz: der Kopf am dichtesten, zum Spurende hin heller
+                    shade_char(1.0 - offset as f32 / trail_len as f32)
+                } else if flicker > 0.0 && self.rng.gen_bool(flicker) {
+                    glyph_at(
+                        &self.charset,
+                        &self.cumulative,
+                        self.rng.gen_range(0..slots),
+                    )
+                } else {
+                    glyph_at(
+                        &self.charset,
+                        &self.cumulative,
+                        self.char_offset + col.phase + col.lane as usize + offset as usize,
+                    )
+                };
+                // Breite Glyphen in der letzten Spalte würden über den Rand ragen, solche
+                // direkt vor dem Rahmen über Rahmen bzw. Ziel-Text
+                let ch_width = ch.width().unwrap_or(1).max(1) as u16;
+                if ch_width == 2
+                    && (x + 1 >= width
+                        || (mirror && x + 1 >= half)
+                        || rain_blocked(targeted, invert, &self.targets, &self.borders, x + 1, y))
+                {
+                    continue;
+                }
+                let cell = Cell {
+                    ch,
+                    fg: color,
+                    bold: offset == 0 && self.config.bold_head,
+                    dim: false,
+                    head: offset == 0,
+                };
+                // Spiegelbild; eine breite Glyphe beginnt dort eine Spalte weiter links,
+                // die Mittelspalte bei ungerader Breite spiegelt auf sich selbst
+                let mirrored = (width - x - ch_width).max(x);
+                let mirrored = (mirror
+                    && mirrored != x
+                    && !(mirrored..mirrored + ch_width).any(|mx| {
+                        rain_blocked(targeted, invert, &self.targets, &self.borders, mx, y)
+                    }))
+                .then_some(mirrored);
+                for x in std::iter::once(x).chain(mirrored) {
+                    let i = y as usize * width as usize + x as usize;
+                    self.grid.set(x, y, cell);
+                    self.painted[i] = self.clock;
+                    if offset == 0 && !self.wake.is_empty() {
+                        self.wake[i] = 1.0;
+                    }
+                }
+            }
+
+            // Liegt der Kopf im Ziel-Block, prüfen wir, ob wir ein Zeichen „einloggen“;
+            // mit `reveal_wpm` bestimmt allein die Zeit das Einloggen
+            if targeted && self.config.reveal_wpm.is_none() && (0..flow_len_i32).contains(&col.head)
+            {
+                let (x, y) = direction.to_xy(lane, col.head as u16, flow_len);
+                for target in &mut self.targets {
+                    // Gespiegelt loggen nur sichtbare Köpfe ein, samt ihrem Spiegelbild
+                    if !mirror {
+                        target.lock_at(x, y, self.clock);
+                    } else if x < half {
+                        target.lock_at(x, y, self.clock);
+                        target.lock_at(width - 1 - x, y, self.clock);
+                    }
+                }
+            }
+
+            // Wenn Kopf hinten raus ist, Spur mit Wahrscheinlichkeit `density` neu starten;
+            // sonst wartet sie und würfelt beim nächsten fälligen Schritt erneut.
+            // Mit `continuous` geht es gleich und knapp vor dem Rand weiter
+            let continuous = self.config.continuous;
+            if col.head >= flow_len_i32 + trail_len
+                && (continuous || density >= 1.0 || self.rng.gen_bool(density))
+            {
+                col.head = if continuous {
+                    -self.rng.gen_range(1..=CONTINUOUS_DELAY)
+                } else {
+                    self.rng.gen_range(-20..0)
+                };
+                col.speed = self.rng.gen_range(self.config.speed_range());
+                col.base_speed = col.speed;
+                col.phase = self.rng.gen_range(0..slots);
+                col.trail_len = self.config.roll_trail_len(&mut self.rng);
+            }
+        }
+
+        if style == Style::Aged {
+            self.age_cells();
+        } else if density < 1.0 {
+            self.expire_cells();
+        }
+        if targeted {
+            let elapsed = self.clock.saturating_sub(self.config.intro_delay);
+            for target in &mut self.targets {
+                target.lock_paced(elapsed, self.clock);
+            }
+        }
+        if let Some(brightness) = self.config.ambient {
+            self.fill_ambient(brightness, targeted, invert);
+        }
+        if targeted && !invert {
+            self.draw_target();
+        }
+        if self.config.celebrate
+            && self.config.marquee.is_none()
+            && targeted
+            && self.celebrated_at.is_none()
+            && self.targets.iter().all(Target::is_complete)
+        {
+            self.celebrated_at = Some(self.clock);
+        }
+    }
+
+    /// Setzt neue Funken an zufällige Stellen bis [`SPARKLE_MARGIN`] Zellen um jeden Rahmen.
+    fn sparkle(&mut self) {
+        let (width, height) = (self.grid.width, self.grid.height);
+        if width == 0 || height == 0 {
+            return;
+        }
+        for b in &self.borders {
+            let (x0, x1) = (
+                b.x0.saturating_sub(SPARKLE_MARGIN),
+                (b.x1 + SPARKLE_MARGIN).min(width - 1),
+            );
+            let (y0, y1) = (
+                b.y0.saturating_sub(SPARKLE_MARGIN),
+                (b.y1 + SPARKLE_MARGIN).min(height - 1),
+            );
+            // Etwa ein Funke auf zwei Zellen Umfang
+            let count = ((x1 - x0) + (y1 - y0)).max(1);
+            for _ in 0..count {
+                let (x, y) = (self.rng.gen_range(x0..=x1), self.rng.gen_range(y0..=y1));
+                if in_border(&self.borders, x, y) {
+                    continue;
+                }
+                let i = y as usize * width as usize + x as usize;
+                self.sparkles.push((x, y, self.grid.cells[i]));
+                self.grid.cells[i] = Cell {
+                    ch: SPARKLE_GLYPH,
+                    fg: Color::White,
+                    bold: true,
+                    dim: false,
+                    head: false,
+                };
+            }
+        }
+    }
+
+    /// Stellt die von Funken verdeckten Zellen wieder her; rückwärts, damit doppelt
+    /// getroffene Zellen ihren ursprünglichen Inhalt zurückbekommen.
+    fn clear_sparkles(&mut self) {
+        for (x, y, cell) in self.sparkles.drain(..).rev() {
+            self.grid.set(x, y, cell);
+        }
+    }
+
+    /// Füllt leere Zellen außerhalb des Ziel-Bereichs mit den festen Zeichen der
+    /// Hintergrundschicht, um `brightness` vom Hintergrund zur Mitte des Verlaufs gemischt.
+    fn fill_ambient(&mut self, brightness: f32, targeted: bool, invert: bool) {
+        let fg = blend_color(
+            self.config.colorset.background(),
+            self.config.colorset.gradient_color(0.5),
+            brightness.clamp(0.0, 1.0),
+        );
+        let width = self.grid.width.max(1) as usize;
+        for (i, ch) in self.ambient.iter().enumerate() {
+            let (x, y) = ((i % width) as u16, (i / width) as u16);
+            if !self.grid.cells[i].is_empty()
+                || rain_blocked(targeted, invert, &self.targets, &self.borders, x, y)
+            {
+                continue;
+            }
+            self.grid.cells[i] = Cell {
+                ch: *ch,
+                fg,
+                bold: false,
+                dim: true,
+                head: false,
+            };
+        }
+    }
+
+    /// Löscht Zellen, die länger als zwei Spurschritte nicht neu gezeichnet wurden –
+    /// also Spurenden und durch `bg_shift` zurückgelassene Spuren.
+    fn expire_cells(&mut self) {
+        let ttl = Duration::from_millis(2 * self.config.speed_range().end());
+        let width = self.grid.width.max(1) as usize;
+        for (i, painted) in self.painted.iter().enumerate() {
+            let (x, y) = ((i % width) as u16, (i / width) as u16);
+            if self.clock - *painted > ttl && !in_border(&self.borders, x, y) {
+                self.grid.cells[i] = Cell::EMPTY;
+            }
+        }
+    }
+
+    /// Färbt Zellen nach ihrem Alter seit dem letzten Zeichnen, gemessen in
+    /// durchschnittlichen Spurschritten. Das Alter läuft über die ganze Spurlänge durch
+    /// den Verlauf, unabhängig von der Zahl der Farbstufen; danach wird die Zelle gelöscht.
+    fn age_cells(&mut self) {
+        let speed = self.config.speed_range();
+        let unit = Duration::from_millis(((speed.start() + speed.end()) / 2).max(1));
+        let max_age = self.config.trail_len.max(1) as usize;
+        let width = self.grid.width.max(1) as usize;
+        for (i, painted) in self.painted.iter().enumerate() {
+            let (x, y) = ((i % width) as u16, (i / width) as u16);
+            let cell = &mut self.grid.cells[i];
+            // Gedimmte Zellen sind Hintergrund (`ambient`), kein Regen
+            if cell.is_empty() || cell.dim || in_border(&self.borders, x, y) {
+                continue;
+            }
+            let age = ((self.clock - *painted).as_millis() / unit.as_millis()) as usize;
+            if age >= max_age {
+                *cell = Cell::EMPTY;
+            } else {
+                cell.fg = match age {
+                    0 if !self.config.bold_head => {
+                        blend_color(self.age_colors[0], Color::White, HEAD_BRIGHTEN)
+                    }
+                    _ => self.age_colors[age],
+                };
+                if age > 0
+                    && let Some(&glow) = self.wake.get(i)
+                {
+                    cell.fg = blend_color(cell.fg, Color::White, glow * WAKE_GLOW);
+                }
+                if self.config.ascii_shading {
+                    cell.ch = shade_char(1.0 - age as f32 / max_age as f32);
+                }
+                cell.bold = age == 0 && self.config.bold_head;
+                cell.head &= age == 0;
+            }
+        }
+    }
+
+    /// Entfernt den im Vorlauf gefallenen Regen dort, wo danach keiner mehr fällt:
+    /// im Rahmen bzw. bei `invert` außerhalb der Glyphen.
+    fn end_intro(&mut self) {
+        if self.config.style == Style::Simple {
+            return;
+        }
+        for y in 0..self.grid.height {
+            for x in 0..self.grid.width {
+                let clear = if self.config.invert && self.config.marquee.is_none() {
+                    !self.targets.iter().any(|t| t.is_glyph(x, y))
+                } else {
+                    in_border(&self.borders, x, y)
+                };
+                if clear {
+                    self.grid.set(x, y, Cell::EMPTY);
+                }
+            }
+        }
+        self.draw_border();
+    }
+
+    fn draw_border(&mut self) {
+        let (width, height) = (self.grid.width, self.grid.height);
+        // Im Vorlauf gibt es noch keinen Rahmen, `end_intro` holt ihn nach
+        if width == 0 || height == 0 || self.clock < self.config.intro_delay {
+            return;
+        }
+        let Some(glyphs) = self.config.border.glyphs() else {
+            return;
+        };
+        let fg = match self.config.border_color {
+            BorderColor::Fixed(color) => color,
+            BorderColor::Auto => self.config.colorset.gradient_color(0.5),
+        };
+        let styled = |ch| Cell {
+            ch,
+            fg,
+            bold: false,
+            dim: false,
+            head: false,
+        };
+
+        for b in &self.borders {
+            if b.x0 <= b.x1 {
+                for x in b.x0..=b.x1 {
+                    // Ecken genau auf x0/x1 × y0/y1, dazwischen waagerechte Kanten
+                    let corner = x == b.x0 || x == b.x1;
+                    let top = match (corner, x == b.x0) {
+                        (false, _) => glyphs.horizontal,
+                        (true, true) => glyphs.top_left,
+                        (true, false) => glyphs.top_right,
+                    };
+                    let bottom = match (corner, x == b.x0) {
+                        (false, _) => glyphs.horizontal,
+                        (true, true) => glyphs.bottom_left,
+                        (true, false) => glyphs.bottom_right,
+                    };
+                    self.grid.set(x, b.y0, styled(top));
+                    if b.y1 != b.y0 {
+                        self.grid.set(x, b.y1, styled(bottom));
+                    }
+                }
+            }
+            if b.y0 < b.y1.saturating_sub(1) && b.x0 <= b.x1 {
+                for y in (b.y0 + 1)..=b.y1.saturating_sub(1) {
+                    self.grid.set(b.x0, y, styled(glyphs.vertical));
+                    if b.x1 != b.x0 {
+                        self.grid.set(b.x1, y, styled(glyphs.vertical));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Löscht die Laufschrift an ihrer bisherigen Stelle, bevor sie weiterrückt;
+    /// der Regen füllt die Lücke mit den nächsten Spuren.
+    fn clear_marquee(&mut self) {
+        let width = self.grid.width;
+        for target in &self.targets {
+            for (row, line) in target.lines.iter().enumerate() {
+                for (col, ch) in line.iter().enumerate() {
+                    if *ch != ' ' {
+                        let x = target.marquee_x(col, self.marquee_shift, width);
+                        self.grid.set(x, target.y + row as u16, Cell::EMPTY);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Ziel-String zeichnen (eingeloggte Zeichen hervorgehoben)
+    fn draw_target(&mut self) {
+        let clock = self.clock;
+        // Vorschau offener Zeichen; `Fade` blendet von hier aus zu Weiß über
+        let ghost = match self.config.ghost_alpha {
+            Some(alpha) => blend_color(self.config.colorset.background(), Color::White, alpha),
+            None => Color::DarkGrey,
+        };
+        // Zufallszeichen für `decrypt` nach Breite getrennt, damit breite Zielzeichen
+        // auch beim Entschlüsseln zwei Zellen belegen
+        let decrypt = self.config.decrypt.map(|_| {
+            let pools = [1, 2].map(|width| {
+                let pool = self.charset.iter().copied();
+                pool.filter(|c| c.width().unwrap_or(1).max(1) == width)
+                    .collect::<Vec<char>>()
+            });
+            (pools, self.config.rain_colors().gradient_color(1.0))
+        });
+        for target in &self.targets {
+            for (row, line) in target.lines.iter().enumerate() {
+                let y = target.y + row as u16;
+                for (col, ch) in line.iter().enumerate() {
+                    if *ch == ' ' || *ch == WIDE_CONT {
+                        continue;
+                    }
+                    let mut x = target.x + col as u16;
+                    if self.config.marquee.is_some() {
+                        x = target.marquee_x(col, self.marquee_shift, self.grid.width);
+                        if ch.width() == Some(2) && x + 1 >= self.grid.width {
+                            continue;
+                        }
+                    }
+                    let scramble = decrypt
+                        .as_ref()
+                        .map(|(pools, fg)| (&pools[usize::from(ch.width() == Some(2))], *fg))
+                        .filter(|(pool, _)| target.scramble[row][col] > 0 && !pool.is_empty());
+                    let cell = if let Some((pool, fg)) = scramble {
+                        Cell {
+                            ch: pool[self.rng.gen_range(0..pool.len())],
+                            fg,
+                            bold: true,
+                            dim: false,
+                            head: false,
+                        }
+                    } else if target.locked[row][col].is_some() {
+                        // Bei `Fade` vom Grau des offenen Zeichens zu Weiß überblenden
+                        let t = match target.reveal {
+                            Reveal::Fade => {
+                                (clock - target.locked_at[row][col]).as_secs_f32()
+                                    / REVEAL_FADE.as_secs_f32()
+                            }
+                            Reveal::Instant | Reveal::Typewriter => 1.0,
+                        };
+                        Cell {
+                            ch: *ch,
+                            fg: if t >= 1.0 {
+                                Color::White
+                            } else {
+                                blend_color(ghost, Color::White, t)
+                            },
+                            bold: t >= 0.5,
+                            dim: false,
+                            head: false,
+                        }
+                    } else {
+                        Cell {
+                            ch: *ch,
+                            fg: ghost,
+                            bold: false,
+                            dim: true,
+                            head: false,
+                        }
+                    };
+                    self.grid.set(x, y, cell);
+                }
+            }
+
+            // Schreibmarke über den offenen Zellen der ersten noch offenen Glyphenspalte
+            if self.config.cursor && (self.frame / CURSOR_BLINK).is_multiple_of(2) {
+                let open = |&&(row, col): &&(usize, usize)| target.locked[row][col].is_none();
+                let rest = &target.order[target.next..];
+                if let Some(&(_, col)) = rest.iter().find(open) {
+                    let cursor = Cell {
+                        ch: CURSOR_GLYPH,
+                        fg: Color::White,
+                        bold: false,
+                        dim: false,
+                        head: false,
+                    };
+                    for &(row, _) in rest
+                        .iter()
+                        .skip_while(|cell| !open(cell))
+                        .take_while(|&&(_, c)| c == col)
+                        .filter(open)
+                    {
+                        self.grid
+                            .set(target.x + col as u16, target.y + row as u16, cursor);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(16);
+
+    /// Zustand mit festem Startwert, ohne Ziel-Text
+    fn rain(config: RainConfig, width: u16, height: u16) -> RainState {
+        let config = RainConfig {
+            style: Style::Simple,
+            seed: Some(7),
+            ..config
+        };
+        RainState::with_config(config, width, height)
+    }
+
+    #[test]
+    fn trail_of_one_draws_only_heads() {
+        let config = RainConfig {
+            trail_len: 1,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 20, 10);
+        let mut prev = state.grid().clone();
+        let mut activated = [false; 20];
+        for _ in 0..300 {
+            let grid = state.step(FRAME);
+            for (x, _, cell) in grid.changes(&prev) {
+                assert!(
+                    cell.is_empty() || cell.head,
+                    "Spurzelle bei x={x}: {cell:?}"
+                );
+                activated[x as usize] |= cell.head;
+            }
+            prev = grid.clone();
+        }
+        // Bei `density` 1.0 kommt jede Spalte zum Zug
+        assert_eq!(activated, [true; 20]);
+    }
+
+    #[test]
+    fn narrow_terminal_truncates_target() {
+        let config = RainConfig {
+            text: "ABCDEFGHIJKLMNOPQRST".into(),
+            seed: Some(1),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 5, 12);
+        for _ in 0..50 {
+            state.step(FRAME);
+        }
+        let target = &state.targets[0];
+        assert_eq!(target.width, 3);
+        assert_eq!(target.x, 1);
+    }
+
+    #[test]
+    fn truncated_target_leaves_room_for_border() {
+        let config = RainConfig {
+            text: "Hallo Welt!".into(),
+            ..RainConfig::default()
+        };
+        let state = RainState::with_config(config.clone(), 40, 12);
+        let target = &state.targets[0];
+        assert_eq!((target.x, target.width), (1, 38));
+        for y in target.y..target.y + target.height {
+            assert_eq!(state.grid().get(0, y).map(|c| c.ch), Some('|'));
+            assert_eq!(state.grid().get(39, y).map(|c| c.ch), Some('|'));
+        }
+        // Ohne Rahmen darf der Text die volle Breite nutzen
+        let config = RainConfig {
+            border: BorderStyle::None,
+            ..config
+        };
+        let state = RainState::with_config(config, 40, 12);
+        assert_eq!((state.targets[0].x, state.targets[0].width), (0, 40));
+    }
+
+    #[test]
+    fn display_cells_follow_terminal_width() {
+        // Kombinationsakzent und Joiner belegen keine Zelle, ein CJK-Zeichen zwei
+        assert_eq!(
+            display_cells("e\u{301}\u{200d}ｱ漢"),
+            ['e', 'ｱ', '漢', WIDE_CONT]
+        );
+    }
+
+    #[test]
+    fn text_lines_stack_as_centered_blocks() {
+        let config = RainConfig {
+            text: "AB\r\nC".into(),
+            ..RainConfig::default()
+        };
+        let state = RainState::with_config(config, 40, 20);
+        let target = &state.targets[0];
+        // Zwei Blöcke à fünf Zeilen, dazwischen eine Leerzeile
+        assert_eq!(target.height, 11);
+        assert!(target.lines[5].iter().all(|&c| c == ' '));
+        // Die kürzere Zeile rückt zur Mitte ein
+        let indent = |row: &[char]| row.iter().position(|&c| c != ' ');
+        assert!(indent(&target.lines[6]) > indent(&target.lines[0]));
+    }
+
+    #[test]
+    fn directions_enter_from_their_edge() {
+        // Position 0 liegt am Eintrittsrand, Spur 3 quer dazu
+        assert_eq!(Direction::Down.to_xy(3, 0, 10), (3, 0));
+        assert_eq!(Direction::Up.to_xy(3, 0, 10), (3, 9));
+        assert_eq!(Direction::Right.to_xy(3, 0, 10), (0, 3));
+        assert_eq!(Direction::Left.to_xy(3, 0, 10), (9, 3));
+        assert_eq!(Direction::Up.to_xy(3, 9, 10), (3, 0));
+    }
+
+    #[test]
+    fn changes_list_only_differing_cells() {
+        let prev = Grid::new(4, 3);
+        let mut grid = prev.clone();
+        let cell = Cell {
+            ch: 'x',
+            ..Cell::EMPTY
+        };
+        grid.set(2, 1, cell);
+        let changed: Vec<_> = grid.changes(&prev).map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(changed, [(2, 1)]);
+        assert_eq!(grid.changes(&grid).count(), 0);
+        // Nach einer Größenänderung ist jede Zelle neu
+        assert_eq!(grid.changes(&Grid::new(2, 2)).count(), 12);
+    }
+
+    #[test]
+    fn same_seed_gives_same_columns() {
+        let columns = |seed| {
+            let config = RainConfig {
+                seed: Some(seed),
+                ..RainConfig::default()
+            };
+            let mut state = RainState::with_config(config, 30, 15);
+            state.step(FRAME);
+            let columns: Vec<_> = state
+                .columns
+                .iter()
+                .map(|c| (c.lane, c.head, c.speed, c.phase, c.trail_len))
+                .collect();
+            (columns, format!("{:?}", state.grid()))
+        };
+        assert_eq!(columns(42), columns(42));
+        assert_ne!(columns(42).0, columns(43).0);
+    }
+
+    #[test]
+    fn zero_density_empties_screen() {
+        let config = RainConfig {
+            density: 0.0,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 20, 10);
+        for _ in 0..100 {
+            state.step(FRAME);
+        }
+        assert!(state.grid().changes(&Grid::new(20, 10)).count() > 0);
+        for _ in 0..2000 {
+            state.step(FRAME);
+        }
+        assert_eq!(state.grid().changes(&Grid::new(20, 10)).count(), 0);
+    }
+
+    #[test]
+    fn equal_speed_bounds_advance_columns_uniformly() {
+        let config = RainConfig {
+            speed_min: 50,
+            speed_max: 50,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 20, 200);
+        let heads = |state: &RainState| state.columns.iter().map(|c| c.head).collect::<Vec<_>>();
+        let mut before = heads(&state);
+        for _ in 0..10 {
+            state.step(Duration::from_millis(50));
+            let after = heads(&state);
+            for (a, b) in before.iter().zip(&after) {
+                assert_eq!(b - a, 1);
+            }
+            before = after;
+        }
+        assert!(state.columns.iter().all(|c| c.speed == 50));
+    }
+
+    #[test]
+    fn every_style_runs() {
+        for &style in Style::value_variants() {
+            for (width, height) in [(40, 12), (3, 2), (1, 1)] {
+                let config = RainConfig {
+                    style,
+                    seed: Some(5),
+                    ..RainConfig::default()
+                };
+                let mut state = RainState::with_config(config, width, height);
+                for _ in 0..200 {
+                    state.step(FRAME);
+                }
+                assert_eq!(
+                    (state.grid().width(), state.grid().height()),
+                    (width, height)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn three_colors_fade_over_ten_ages() {
+        let config = RainConfig {
+            colorset: ColorSet::from_hex(&["#ff0000", "#00ff00", "#0000ff"]),
+            trail_len: 10,
+            ..RainConfig::default()
+        };
+        let colors = age_colors(&config);
+        assert_eq!(colors.len(), 10);
+        assert_eq!(colors[0], Color::Rgb { r: 255, g: 0, b: 0 });
+        assert_eq!(colors[9], Color::Rgb { r: 0, g: 0, b: 255 });
+        // Jede Stufe eine eigene Farbe, nicht nur die drei Stützfarben
+        for pair in colors.windows(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn trail_colors_run_from_head_to_tail() {
+        let config = RainConfig {
+            trail_len: 4,
+            ..RainConfig::default()
+        };
+        // Tabelle je Spurlänge; ohne --trail-min gibt es nur die eine
+        let colors = trail_colors(&config).swap_remove(4);
+        assert_eq!(colors.len(), 4);
+        assert_eq!(colors[0], config.colorset.gradient_color(1.0));
+        assert_eq!(colors[3], config.colorset.gradient_color(0.0));
+    }
+
+    /// Relative Helligkeit nach Rec. 709, ohne Linearisierung
+    fn luminance(color: Color) -> f32 {
+        let (r, g, b) = crate::color::color_to_rgb(color);
+        0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32
+    }
+
+    #[test]
+    fn glow_brightens_the_head() {
+        let head = |glow| {
+            let config = RainConfig {
+                glow,
+                ..RainConfig::default()
+            };
+            let len = config.trail_len as usize;
+            let colors = trail_colors(&config).swap_remove(len);
+            (colors[0], colors[3])
+        };
+        let (plain, plain_tail) = head(0.0);
+        let (lit, lit_tail) = head(0.6);
+        assert!(luminance(lit) > luminance(plain));
+        // Ab der dritten Zelle hinter dem Kopf wirkt das Leuchten nicht mehr
+        assert_eq!(lit_tail, plain_tail);
+    }
+
+    #[test]
+    fn column_hue_offsets_tint_the_heads() {
+        let config = RainConfig {
+            colorset: ColorSet::from_hex(&["#003300", "#00ff00"]),
+            column_hue: 90.0,
+            scroll_speed: 0,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 3, 20);
+        for (col, hue) in state.columns.iter_mut().zip([-60.0, 0.0, 60.0]) {
+            col.head = 5;
+            col.speed = 20;
+            col.base_speed = 20;
+            col.hue = hue;
+        }
+        state.step(Duration::from_millis(20));
+        let heads: Vec<Color> = (0..3)
+            .map(|x| {
+                let cell = state.grid().get(x, 6).unwrap();
+                assert!(cell.head, "kein Kopf in Spalte {x}");
+                cell.fg
+            })
+            .collect();
+        assert_eq!(heads[1], state.config.rain_colors().gradient_color(1.0));
+        assert_ne!(heads[0], heads[1]);
+        assert_ne!(heads[0], heads[2]);
+        assert_ne!(heads[1], heads[2]);
+    }
+
+    #[test]
+    fn flicker_changes_trail_glyphs_but_not_the_text() {
+        // Spurzellen, die nicht das Zeichen aus der Rotation zeigen
+        let flickered = |flicker| {
+            let config = RainConfig {
+                flicker,
+                trail_len: 12,
+                shimmer: 0,
+                scroll_speed: 0,
+                ..RainConfig::default()
+            };
+            let mut state = rain(config, 1, 30);
+            let col = &mut state.columns[0];
+            (col.head, col.speed, col.base_speed, col.trail_len) = (14, 20, 20, 12);
+            state.step(Duration::from_millis(20));
+            let col = &state.columns[0];
+            (0..12)
+                .filter(|&offset| {
+                    let slot = state.char_offset + col.phase + col.lane as usize + offset;
+                    let expected = glyph_at(&state.charset, &state.cumulative, slot);
+                    state
+                        .grid()
+                        .get(0, (col.head as usize - offset) as u16)
+                        .unwrap()
+                        .ch
+                        != expected
+                })
+                .count()
+        };
+        assert_eq!(flickered(0.0), 0);
+        assert!(flickered(1.0) > 6);
+
+        let config = RainConfig {
+            text: "HI".into(),
+            flicker: 1.0,
+            seed: Some(8),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 30, 12);
+        for _ in 0..3000 {
+            state.step(FRAME);
+            let target = &state.targets[0];
+            for (row, line) in target.lines.iter().enumerate() {
+                for (col, &ch) in line.iter().enumerate() {
+                    if target.locked[row][col].is_some() {
+                        let (x, y) = (target.x + col as u16, target.y + row as u16);
+                        assert_eq!(state.grid().get(x, y).unwrap().ch, ch);
+                    }
+                }
+            }
+            if state.is_complete() {
+                return;
+            }
+        }
+        panic!("Text nie vollständig");
+    }
+
+    #[test]
+    fn typewriter_locks_in_reading_order() {
+        let config = RainConfig {
+            text: "Hallo".into(),
+            reveal: Reveal::Typewriter,
+            seed: Some(3),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 40, 12);
+        for _ in 0..3000 {
+            state.step(FRAME);
+            let target = &state.targets[0];
+            let locked: Vec<bool> = target
+                .order
+                .iter()
+                .map(|&(row, col)| target.locked[row][col].is_some())
+                .collect();
+            // Eingeloggt ist immer ein Anfangsstück der Lesereihenfolge
+            let count = locked.iter().take_while(|&&l| l).count();
+            assert!(locked[count..].iter().all(|&l| !l), "{locked:?}");
+        }
+        assert!(state.is_complete());
+    }
+
+    #[test]
+    fn border_modes_draw_their_corners() {
+        let cases = [
+            (BorderStyle::Ascii, ['+', '+', '+', '+']),
+            (BorderStyle::Rounded, ['╭', '╮', '╰', '╯']),
+            (BorderStyle::Double, ['╔', '╗', '╚', '╝']),
+        ];
+        for (border, corners) in cases {
+            let config = RainConfig {
+                text: "Hi".into(),
+                border,
+                seed: Some(4),
+                ..RainConfig::default()
+            };
+            let mut state = RainState::with_config(config, 30, 12);
+            let grid = state.step(FRAME).clone();
+            let b = &state.borders[0];
+            let at = |x, y| grid.get(x, y).map(|c| c.ch);
+            // Früher fehlten die Ecken, wenn der Rahmen die Textzeile nicht berührte
+            assert_eq!(
+                [
+                    at(b.x0, b.y0),
+                    at(b.x1, b.y0),
+                    at(b.x0, b.y1),
+                    at(b.x1, b.y1)
+                ],
+                corners.map(Some),
+                "{border:?}"
+            );
+        }
+        assert!(BorderStyle::None.glyphs().is_none());
+    }
+
+    #[test]
+    fn border_color_is_fixed_or_from_the_palette() {
+        let corner = |border_color| {
+            let config = RainConfig {
+                text: "Hi".into(),
+                border_color,
+                seed: Some(4),
+                ..RainConfig::default()
+            };
+            let mut state = RainState::with_config(config, 30, 12);
+            let grid = state.step(FRAME).clone();
+            let b = &state.borders[0];
+            grid.get(b.x0, b.y0).unwrap().fg
+        };
+        let orange = crate::color::hex_to_color("#ff8800").unwrap();
+        assert_eq!(corner(BorderColor::Fixed(orange)), orange);
+        assert_eq!(
+            corner(BorderColor::Auto),
+            RainConfig::default().colorset.gradient_color(0.5)
+        );
+    }
+
+    #[test]
+    fn border_is_emitted_once() {
+        let config = RainConfig {
+            text: "Hi".into(),
+            seed: Some(6),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 30, 12);
+        let b = &state.borders[0];
+        let (x0, x1, y0, y1) = (b.x0, b.x1, b.y0, b.y1);
+        let on_border = |x: u16, y: u16| {
+            (x == x0 || x == x1 || y == y0 || y == y1)
+                && (x0..=x1).contains(&x)
+                && (y0..=y1).contains(&y)
+        };
+        let mut prev = Grid::new(30, 12);
+        let mut emitted = 0;
+        for _ in 0..500 {
+            let grid = state.step(FRAME);
+            emitted += grid
+                .changes(&prev)
+                .filter(|&(x, y, _)| on_border(x, y))
+                .count();
+            prev = grid.clone();
+        }
+        let perimeter = 2 * (x1 - x0 + 1) as usize + 2 * (y1 - y0 - 1) as usize;
+        assert_eq!(emitted, perimeter);
+    }
+
+    #[test]
+    fn shrinking_width_drops_columns() {
+        let mut state = RainState::with_config(RainConfig::default(), 40, 12);
+        for _ in 0..50 {
+            state.step(FRAME);
+        }
+        state.resize(25, 12);
+        assert_eq!(state.columns.len(), 25);
+        assert!(state.columns.iter().all(|c| c.lane < 25));
+        // Spuren jenseits der neuen Breite dürfen nicht mehr ins Raster schreiben
+        for _ in 0..200 {
+            assert_eq!(state.step(FRAME).width(), 25);
+        }
+        state.resize(60, 12);
+        assert_eq!(state.columns.len(), 60);
+
+        let mut state = RainState::with_config(
+            RainConfig {
+                direction: Direction::Left,
+                ..RainConfig::default()
+            },
+            40,
+            12,
+        );
+        state.resize(40, 5);
+        assert_eq!(state.columns.len(), 5);
+    }
+
+    #[test]
+    fn very_tall_grid_does_not_overflow() {
+        let height = u16::MAX;
+        let config = RainConfig {
+            trail_len: 1000,
+            speed_min: 1,
+            speed_max: 1,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 2, height);
+        // Köpfe direkt vor die `i16`-Grenze setzen, statt 32 000 Schritte zu rechnen
+        for col in &mut state.columns {
+            col.head = i16::MAX as i32 - 5;
+        }
+        for _ in 0..20 {
+            state.step(Duration::from_millis(1));
+        }
+        assert!(state.columns.iter().all(|c| c.head > i16::MAX as i32));
+
+        // Kurz vor dem Ende des Rasters samt Spur starten die Spuren neu
+        for col in &mut state.columns {
+            col.head = height as i32 + 995;
+        }
+        let mut restarted = [false; 2];
+        for _ in 0..20 {
+            state.step(Duration::from_millis(1));
+            for (done, col) in restarted.iter_mut().zip(&state.columns) {
+                *done |= col.head < 0;
+            }
+        }
+        assert_eq!(restarted, [true; 2]);
+    }
+
+    #[test]
+    fn screen_gradient_colors_by_row() {
+        let config = RainConfig {
+            gradient: Gradient::Screen,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 30, 12);
+        for _ in 0..300 {
+            state.step(FRAME);
+        }
+        let grid = state.grid();
+        for y in 0..12 {
+            let mut colors = (0..30)
+                .filter_map(|x| grid.get(x, y))
+                .filter(|c| !c.is_empty())
+                .map(|c| c.fg);
+            let first = colors.next();
+            assert!(colors.all(|fg| Some(fg) == first), "Zeile {y}");
+        }
+        // Oben Anfang, unten Ende des Verlaufs
+        let config = &state.config;
+        let rows = screen_colors(config, 30, 12);
+        assert_eq!(rows[0], config.colorset.gradient_color(0.0));
+        assert_eq!(rows[11], config.colorset.gradient_color(1.0));
+    }
+
+    #[test]
+    fn revealed_text_matches_glyph_rows() {
+        let config = RainConfig {
+            text: "HI".into(),
+            seed: Some(8),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 30, 12);
+        while !state.is_complete() {
+            state.step(FRAME);
+        }
+        let expected: String = render_text_spaced("HI", Font::F3x5, 1)
+            .iter()
+            .map(|row| format!("{}\n", row.trim_end()))
+            .collect();
+        assert_eq!(state.revealed_text(), expected);
+    }
+
+    #[test]
+    fn invert_keeps_rain_inside_the_glyphs() {
+        let config = RainConfig {
+            text: "HI".into(),
+            invert: true,
+            seed: Some(9),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 30, 12);
+        let mut rained = 0;
+        for _ in 0..300 {
+            state.step(FRAME);
+            let target = &state.targets[0];
+            for (x, y, _, _, _) in state.frame_cells() {
+                let inside = (target.x..target.x + target.width).contains(&x)
+                    && (target.y..target.y + target.height).contains(&y);
+                // Außerhalb des Glyphenblocks steht höchstens der Rahmen
+                assert!(in_border(&state.borders, x, y), "Regen bei ({x}, {y})");
+                rained += inside as usize;
+            }
+        }
+        assert!(rained > 0);
+    }
+
+    #[test]
+    fn nothing_locks_during_intro() {
+        let config = RainConfig {
+            text: "HI".into(),
+            intro_delay: Duration::from_secs(2),
+            seed: Some(10),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 30, 12);
+        // 120 Frames zu 16 ms bleiben unter zwei Sekunden
+        for _ in 0..120 {
+            state.step(FRAME);
+            assert_eq!(state.targets[0].locked_count, 0);
+        }
+        for _ in 0..600 {
+            state.step(FRAME);
+        }
+        assert!(state.targets[0].locked_count > 0);
+    }
+
+    #[test]
+    fn equal_trail_bounds_share_one_length() {
+        let config = RainConfig {
+            trail_min: Some(6),
+            trail_len: 6,
+            density: 0.5,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 40, 8);
+        for _ in 0..500 {
+            state.step(FRAME);
+            assert!(state.columns.iter().all(|c| c.trail_len == 6));
+        }
+
+        // Mit echter Spanne würfelt jede Spur ihre eigene Länge
+        let config = RainConfig {
+            trail_min: Some(2),
+            trail_len: 12,
+            ..RainConfig::default()
+        };
+        let state = rain(config, 40, 8);
+        let lengths: std::collections::HashSet<_> =
+            state.columns.iter().map(|c| c.trail_len).collect();
+        assert!(lengths.len() > 1);
+        assert!(lengths.iter().all(|len| (2..=12).contains(len)));
+    }
+
+    #[test]
+    fn frame_cells_flag_heads() {
+        // Ohne Bildlauf bleibt jede Spur in ihrer Spalte
+        let config = RainConfig {
+            scroll_speed: 0,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 20, 10);
+        for _ in 0..60 {
+            state.step(FRAME);
+        }
+        let heads: Vec<(u16, u16)> = state
+            .frame_cells()
+            .filter(|&(_, _, _, _, head)| head)
+            .map(|(x, y, _, _, _)| (x, y))
+            .collect();
+        let visible: Vec<(u16, u16)> = state
+            .columns
+            .iter()
+            .filter(|c| (0..10).contains(&c.head))
+            // Der Hintergrund-Bildlauf verschiebt die Spuren quer zur Fließrichtung
+            .map(|c| ((c.lane + state.bg_shift) % 20, c.head as u16))
+            .collect();
+        assert!(!visible.is_empty());
+        for head in &visible {
+            assert!(heads.contains(head), "{head:?} nicht als Kopf markiert");
+        }
+        assert_eq!(heads.len(), visible.len());
+    }
+
+    #[test]
+    fn depth_shading_brightens_fast_heads() {
+        let config = RainConfig {
+            depth_shading: true,
+            speed_min: 20,
+            speed_max: 200,
+            scroll_speed: 0,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 2, 30);
+        for (col, speed) in state.columns.iter_mut().zip([20, 200]) {
+            col.head = 5;
+            col.speed = speed;
+            col.base_speed = speed;
+        }
+        state.step(Duration::from_millis(200));
+        let head = |x| state.grid().get(x, 6).map(|c| (c.head, luminance(c.fg)));
+        let (Some((true, fast)), Some((true, slow))) = (head(0), head(1)) else {
+            panic!("Köpfe fehlen: {:?} {:?}", head(0), head(1));
+        };
+        assert!(fast > slow, "{fast} <= {slow}");
+    }
+
+    #[test]
+    fn fade_in_starts_dark() {
+        let config = RainConfig {
+            fade_in: Duration::from_secs(5),
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 20, 10);
+        let brightest = |state: &RainState| {
+            state
+                .frame_cells()
+                .map(|(_, _, _, fg, _)| luminance(fg))
+                .fold(0.0, f32::max)
+        };
+        // Erst sobald etwas sichtbar ist, taugt der Vergleich
+        while state.frame_cells().next().is_none() {
+            state.step(FRAME);
+        }
+        let early = brightest(&state);
+        for _ in 0..400 {
+            state.step(FRAME);
+        }
+        assert!(early < brightest(&state) * 0.5, "{early}");
+    }
+
+    #[test]
+    fn shimmer_zero_keeps_offset() {
+        let mut state = rain(
+            RainConfig {
+                shimmer: 0,
+                ..RainConfig::default()
+            },
+            20,
+            10,
+        );
+        for _ in 0..100 {
+            state.step(FRAME);
+            assert_eq!(state.char_offset, 0);
+        }
+
+        let mut state = rain(
+            RainConfig {
+                shimmer: 3,
+                ..RainConfig::default()
+            },
+            20,
+            10,
+        );
+        for _ in 0..30 {
+            state.step(FRAME);
+        }
+        assert_eq!(state.char_offset, 10);
+    }
+
+    #[test]
+    fn gravity_shortens_each_step() {
+        let config = RainConfig {
+            gravity: true,
+            speed_min: 100,
+            speed_max: 100,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 1, 40);
+        state.columns[0].head = 0;
+        let mut last = state.columns[0].speed;
+        let mut shrunk = 0;
+        for _ in 0..500 {
+            state.step(FRAME);
+            let col = &state.columns[0];
+            if col.head < 0 {
+                // Neustart: wieder mit der Grundgeschwindigkeit
+                assert_eq!(col.speed, col.base_speed);
+                break;
+            }
+            assert!(col.speed <= last, "{} > {last}", col.speed);
+            shrunk += (col.speed < last) as usize;
+            last = col.speed;
+        }
+        assert!(shrunk > 10, "{shrunk}");
+    }
+
+    #[test]
+    fn ghost_alpha_zero_hides_unlocked_glyphs() {
+        let ghost_of = |alpha| {
+            let config = RainConfig {
+                text: "HI".into(),
+                ghost_alpha: Some(alpha),
+                ..RainConfig::default()
+            };
+            let mut state = RainState::with_config(config, 30, 12);
+            state.step(FRAME);
+            let background = crate::color::color_to_rgb(state.config.colorset.background());
+            let target = &state.targets[0];
+            let mut ghosts = Vec::new();
+            for (row, line) in target.lines.iter().enumerate() {
+                for (col, &ch) in line.iter().enumerate() {
+                    if ch == ' ' || target.locked[row][col].is_some() {
+                        continue;
+                    }
+                    let (x, y) = (target.x + col as u16, target.y + row as u16);
+                    let cell = state.grid().get(x, y).unwrap();
+                    ghosts.push(crate::color::color_to_rgb(cell.fg) == background);
+                }
+            }
+            assert!(!ghosts.is_empty());
+            ghosts
+        };
+        assert!(ghost_of(0.0).iter().all(|&bg| bg));
+        assert!(ghost_of(0.5).iter().all(|&bg| !bg));
+    }
+
+    #[test]
+    fn rain_stays_off_the_target_frame() {
+        let config = RainConfig {
+            text: "HI".into(),
+            charset: vec!['日', '本', 'X'],
+            seed: Some(11),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 30, 12);
+        for _ in 0..600 {
+            state.step(FRAME);
+            let b = &state.borders[0];
+            for (x, y, ch, _, head) in state.frame_cells() {
+                if b.contains(x, y) {
+                    assert!(!head && !"日本X".contains(ch), "Regen bei ({x}, {y}): {ch}");
+                }
+                // Eine breite Glyphe direkt links vom Rahmen würde in ihn hineinragen
+                if x + 1 == b.x0 && (b.y0..=b.y1).contains(&y) {
+                    assert_eq!(ch.width(), Some(1), "({x}, {y}): {ch}");
+                }
+            }
+        }
+        assert!(state.targets[0].locked_count > 0);
+        // Ohne Ziel-Text regnet es überall
+        assert!(!rain_blocked(
+            false,
+            false,
+            &state.targets,
+            &state.borders,
+            15,
+            6
+        ));
+        let t = &state.targets[0];
+        assert!(rain_blocked(
+            true,
+            false,
+            &state.targets,
+            &state.borders,
+            t.x,
+            t.y
+        ));
+    }
+
+    #[test]
+    fn scroll_speed_is_clamped() {
+        let mut state = rain(RainConfig::default(), 10, 5);
+        state.set_scroll_speed(200);
+        assert_eq!(state.config().scroll_speed, 10);
+        assert_eq!(state.scroll_interval, 1);
+        state.set_scroll_speed(0);
+        assert_eq!(state.scroll_interval, u16::MAX);
+    }
+
+    #[test]
+    fn line_spacing_inserts_blank_rows() {
+        let lines = |line_spacing| {
+            let config = RainConfig {
+                text: "A\nB".into(),
+                line_spacing,
+                ..RainConfig::default()
+            };
+            RainState::with_config(config, 40, 30).targets[0]
+                .lines
+                .clone()
+        };
+        let blank = |row: &Vec<char>| row.iter().all(|&c| c == ' ');
+        let spaced = lines(2);
+        // Zwei 3x5-Blöcke, dazwischen genau zwei Leerzeilen und keine danach
+        assert_eq!(spaced.len(), 5 + 2 + 5);
+        assert!(spaced[5..7].iter().all(blank));
+        assert!(!blank(&spaced[4]) && !blank(&spaced[7]) && !blank(&spaced[11]));
+        assert_eq!(lines(0).len(), 10);
+    }
+
+    #[test]
+    fn ambient_is_dimmer_than_rain() {
+        // Helligkeit steigt entlang des Verlaufs; bei Sets mit dunkler Mitte kann die
+        // Hintergrundschicht heller sein als einzelne Spurfarben
+        let config = RainConfig {
+            colorset: ColorSet::from_hex(&["#005500", "#00ff66"]),
+            ambient: Some(0.3),
+            density: 0.5,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 20, 10);
+        for _ in 0..200 {
+            state.step(FRAME);
+        }
+        let cells: Vec<&Cell> = state
+            .grid()
+            .rows()
+            .flatten()
+            .filter(|c| !c.is_empty())
+            .collect();
+        let (ambient, trail): (Vec<&Cell>, Vec<&Cell>) = cells.into_iter().partition(|c| c.dim);
+        assert!(!ambient.is_empty() && !trail.is_empty());
+        let brightest = ambient.iter().map(|c| luminance(c.fg)).fold(0.0, f32::max);
+        let dimmest = trail
+            .iter()
+            .map(|c| luminance(c.fg))
+            .fold(f32::MAX, f32::min);
+        assert!(brightest < dimmest, "{brightest} >= {dimmest}");
+    }
+
+    #[test]
+    fn burst_resets_columns_near_the_click() {
+        let config = RainConfig {
+            scroll_speed: 0,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 30, 12);
+        for col in &mut state.columns {
+            col.head = -15;
+        }
+        state.burst(10, 6);
+        for col in &state.columns {
+            if col.lane.abs_diff(10) <= BURST_RADIUS {
+                assert!(
+                    (4..=6).contains(&col.head),
+                    "Spur {}: {}",
+                    col.lane,
+                    col.head
+                );
+            } else {
+                assert_eq!(col.head, -15, "Spur {}", col.lane);
+            }
+        }
+        // Klicks neben das Raster ändern nichts
+        state.burst(30, 0);
+        assert_eq!(state.columns[29].head, -15);
+    }
+
+    #[test]
+    fn reveal_wpm_paces_the_text() {
+        // 60 Wörter pro Minute = fünf Zeichen pro Sekunde, "HALLO" also in einer Sekunde
+        let config = RainConfig {
+            text: "HALLO".into(),
+            reveal_wpm: Some(60),
+            seed: Some(12),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 40, 12);
+        let total = state.targets[0].order.len();
+        let step_ms = 10;
+        let mut done_at = None;
+        for frame in 1..=200 {
+            state.step(Duration::from_millis(step_ms));
+            let elapsed = frame * step_ms;
+            let locked = state.targets[0].locked_count;
+            if elapsed == 500 {
+                let share = locked as f32 / total as f32;
+                assert!((0.45..=0.55).contains(&share), "{locked}/{total}");
+            }
+            if locked == total && done_at.is_none() {
+                done_at = Some(elapsed);
+            }
+        }
+        assert!(
+            done_at.is_some_and(|ms| (950..=1050).contains(&ms)),
+            "{done_at:?}"
+        );
+    }
+
+    #[test]
+    fn bold_head_can_be_turned_off() {
+        let heads = |bold_head| {
+            let config = RainConfig {
+                bold_head,
+                ..RainConfig::default()
+            };
+            let mut state = rain(config, 20, 10);
+            for _ in 0..100 {
+                state.step(FRAME);
+            }
+            let heads: Vec<Cell> = state
+                .grid()
+                .rows()
+                .flatten()
+                .filter(|c| c.head)
+                .copied()
+                .collect();
+            assert!(!heads.is_empty());
+            heads
+        };
+        assert!(heads(true).iter().all(|c| c.bold));
+        assert!(heads(false).iter().all(|c| !c.bold));
+    }
+
+    #[test]
+    fn wake_decays_after_the_head_passes() {
+        let config = RainConfig {
+            wake: Some(0.8),
+            speed_min: 1000,
+            speed_max: 1000,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 1, 20);
+        state.columns[0].head = 4;
+        // Ein Schritt: der Kopf erreicht Zeile 5 und entzündet dort das Nachleuchten
+        state.step(Duration::from_millis(1000));
+        let i = 5;
+        assert_eq!(state.wake[i], 1.0);
+        let mut last = state.wake[i];
+        for _ in 0..20 {
+            state.step(FRAME);
+            assert!(state.wake[i] < last, "{} >= {last}", state.wake[i]);
+            last = state.wake[i];
+        }
+    }
+
+    #[test]
+    fn mirror_reflects_the_left_half() {
+        let config = RainConfig {
+            mirror: true,
+            scroll_speed: 0,
+            ..RainConfig::default()
+        };
+        let width = 21;
+        let mut state = rain(config, width, 10);
+        let mut heads = 0;
+        for _ in 0..200 {
+            let grid = state.step(FRAME);
+            for y in 0..10 {
+                for x in 0..width / 2 {
+                    let (left, right) =
+                        (grid.get(x, y).unwrap(), grid.get(width - 1 - x, y).unwrap());
+                    assert_eq!((left.ch, left.head), (right.ch, right.head), "({x}, {y})");
+                    heads += left.head as usize;
+                }
+            }
+        }
+        assert!(heads > 0);
+    }
+
+    #[test]
+    fn cursor_tracks_the_first_unlocked_column() {
+        let config = RainConfig {
+            text: "Hallo".into(),
+            reveal: Reveal::Typewriter,
+            cursor: true,
+            seed: Some(3),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 40, 12);
+        let cursor_columns = |state: &RainState| {
+            let grid = state.grid();
+            let is_cursor = |x, y| grid.get(x, y).unwrap().ch == CURSOR_GLYPH;
+            (0..grid.width())
+                .filter(|&x| (0..grid.height()).any(|y| is_cursor(x, y)))
+                .collect::<Vec<u16>>()
+        };
+        let mut seen = 0;
+        while !state.is_complete() {
+            state.step(FRAME);
+            let target = &state.targets[0];
+            let first_open = target
+                .order
+                .iter()
+                .find(|&&(row, col)| target.locked[row][col].is_none());
+            let expected = match first_open {
+                Some(&(_, col)) if (state.frame / CURSOR_BLINK).is_multiple_of(2) => {
+                    seen += 1;
+                    vec![target.x + col as u16]
+                }
+                _ => Vec::new(),
+            };
+            assert_eq!(cursor_columns(&state), expected, "Frame {}", state.frame);
+            assert!(state.frame < 5000, "Text nie vollständig");
+        }
+        assert!(seen > 0);
+        // Ist der Text vollständig, verschwindet die Schreibmarke
+        for _ in 0..2 * CURSOR_BLINK {
+            state.step(FRAME);
+            assert!(cursor_columns(&state).is_empty());
+        }
+    }
+
+    #[test]
+    fn jitter_varies_the_step_interval() {
+        let config = RainConfig {
+            speed_min: 50,
+            speed_max: 50,
+            jitter: 20,
+            scroll_speed: 0,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 1, 200);
+        state.columns[0].head = 0;
+        let mut intervals = std::collections::BTreeSet::new();
+        let (mut last_head, mut since) = (state.columns[0].head, 0);
+        while state.columns[0].head < 150 {
+            state.step(Duration::from_millis(1));
+            since += 1;
+            if state.columns[0].head != last_head {
+                assert!((30..=70).contains(&since), "{since} ms");
+                intervals.insert(since);
+                (last_head, since) = (state.columns[0].head, 0);
+            }
+        }
+        assert!(intervals.len() > 1, "{intervals:?}");
+    }
+
+    #[test]
+    fn text_align_places_the_block_vertically() {
+        // Fünf Glyphzeilen in 20 Bildschirmzeilen
+        let y = |text_align| {
+            let config = RainConfig {
+                text: "HI".into(),
+                text_align,
+                ..RainConfig::default()
+            };
+            let state = RainState::with_config(config, 30, 20);
+            assert_eq!(state.targets[0].lines.len(), 5);
+            state.targets[0].y
+        };
+        assert_eq!(y(TextAlign::Top), 1);
+        assert_eq!(y(TextAlign::Center), 7);
+        assert_eq!(y(TextAlign::Bottom), 14);
+        assert_eq!(y(TextAlign::Row(9)), 9);
+        // Außerhalb wird auf die Zeilen begrenzt, in die Text und Rahmen passen
+        assert_eq!(y(TextAlign::Row(0)), 1);
+        assert_eq!(y(TextAlign::Row(99)), 14);
+    }
+
+    #[test]
+    fn head_and_tail_take_their_colors() {
+        let head_color = Color::Rgb { r: 255, g: 0, b: 0 };
+        let tail_color = Color::Rgb { r: 0, g: 0, b: 255 };
+        let config = RainConfig {
+            trail_gradient: Some((head_color, tail_color)),
+            trail_len: 6,
+            scroll_speed: 0,
+            density: 0.4,
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 30, 20);
+        for _ in 0..120 {
+            state.step(FRAME);
+        }
+        let grid = state.grid();
+        let mut checked = 0;
+        for x in 0..grid.width() {
+            for y in 5..grid.height() {
+                let (head, tail) = (grid.get(x, y).unwrap(), grid.get(x, y - 5).unwrap());
+                if head.head && !tail.is_empty() {
+                    assert_eq!((head.fg, tail.fg), (head_color, tail_color), "Spalte {x}");
+                    checked += 1;
+                }
+            }
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn heavy_glyph_appears_more_often() {
+        let config = RainConfig {
+            charset: vec!['a', 'b'],
+            charset_weights: vec![('a', 9)],
+            ..RainConfig::default()
+        };
+        let mut state = rain(config, 40, 20);
+        let (mut a, mut b) = (0, 0);
+        for _ in 0..300 {
+            state.step(FRAME);
+            for (_, _, ch, _, _) in state.frame_cells() {
+                a += (ch == 'a') as usize;
+                b += (ch == 'b') as usize;
+            }
+        }
+        assert!(b > 0 && a > 5 * b, "a={a} b={b}");
+    }
+
+    #[test]
+    fn celebration_only_follows_completion() {
+        let config = RainConfig {
+            text: "OK".into(),
+            celebrate: true,
+            charset: vec!['0', '1'],
+            seed: Some(6),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 30, 12);
+        let sparkling =
+            |state: &RainState| state.grid().rows().flatten().any(|c| c.ch == SPARKLE_GLYPH);
+        loop {
+            state.step(FRAME);
+            if state.is_complete() {
+                break;
+            }
+            assert!(!state.is_celebrating() && !sparkling(&state));
+            assert!(
+                state.clock < Duration::from_secs(120),
+                "Text nie vollständig"
+            );
+        }
+        // Die Feier beginnt mit dem letzten Zeichen, die Funken ab dem nächsten Frame
+        assert!(state.is_celebrating());
+        state.step(FRAME);
+        assert!(sparkling(&state));
+    }
+
+    #[test]
+    fn two_strings_lock_independently() {
+        let config = RainConfig {
+            text: "HI".into(),
+            text_align: TextAlign::Top,
+            extra_texts: vec![TextBlock {
+                text: "LO".into(),
+                align: TextAlign::Bottom,
+                halign: TextHAlign::Center,
+            }],
+            seed: Some(13),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 30, 16);
+        assert!(!state.targets_overlap());
+
+        // Ein Treffer im ersten Block lässt den zweiten unberührt
+        let (row, col) = state.targets[0].order[0];
+        let (x, y) = (
+            state.targets[0].x + col as u16,
+            state.targets[0].y + row as u16,
+        );
+        for target in &mut state.targets {
+            target.lock_at(x, y, Duration::ZERO);
+        }
+        assert_eq!(state.targets[0].locked_count, 1);
+        assert_eq!(state.targets[1].locked_count, 0);
+
+        while !state.is_complete() {
+            state.step(FRAME);
+        }
+        let revealed = state.revealed_text();
+        let blocks: Vec<&str> = revealed.split("\n\n").collect();
+        assert_eq!(blocks.len(), 2, "{revealed}");
+        assert_ne!(blocks[0], blocks[1]);
+    }
+
+    #[test]
+    fn overlapping_strings_are_detected() {
+        let config = RainConfig {
+            text: "HI".into(),
+            extra_texts: vec![TextBlock {
+                text: "LO".into(),
+                ..TextBlock::default()
+            }],
+            ..RainConfig::default()
+        };
+        assert!(RainState::with_config(config, 30, 16).targets_overlap());
+    }
+
+    #[test]
+    fn continuous_keeps_the_screen_occupied() {
+        // Mittlerer Anteil belegter Zellen nach dem Anlaufen
+        let occupancy = |continuous| {
+            let config = RainConfig {
+                continuous,
+                density: 0.01,
+                scroll_speed: 0,
+                ..RainConfig::default()
+            };
+            let mut state = rain(config, 40, 20);
+            let mut filled = 0;
+            for frame in 0..600 {
+                state.step(FRAME);
+                if frame >= 200 {
+                    filled += state
+                        .grid()
+                        .rows()
+                        .flatten()
+                        .filter(|c| !c.is_empty())
+                        .count();
+                }
+            }
+            filled as f32 / (400 * 40 * 20) as f32
+        };
+        let (on, off) = (occupancy(true), occupancy(false));
+        assert!(on > 0.3 && off < 0.15, "mit {on}, ohne {off}");
+    }
+
+    #[test]
+    fn reverse_gradient_brightens_the_tail() {
+        let ends = |reverse_gradient| {
+            let config = RainConfig {
+                colorset: ColorSet::from_hex(&["#003300", "#00ff00"]),
+                trail_len: 6,
+                reverse_gradient,
+                scroll_speed: 0,
+                density: 0.4,
+                ..RainConfig::default()
+            };
+            let mut state = rain(config, 30, 20);
+            for _ in 0..120 {
+                state.step(FRAME);
+            }
+            // Kopf und letzte Spurzelle jeder Spalte, deren Spur ganz zu sehen ist
+            let grid = state.grid();
+            let mut pairs = Vec::new();
+            for x in 0..grid.width() {
+                for y in 5..grid.height() {
+                    let (head, tail) = (grid.get(x, y).unwrap(), grid.get(x, y - 5).unwrap());
+                    if head.head && !tail.is_empty() {
+                        pairs.push((luminance(head.fg), luminance(tail.fg)));
+                    }
+                }
+            }
+            assert!(!pairs.is_empty());
+            pairs
+        };
+        assert!(ends(false).iter().all(|(head, tail)| head > tail));
+        assert!(ends(true).iter().all(|(head, tail)| head < tail));
+    }
+
+    #[test]
+    fn decrypt_scrambles_for_exactly_n_frames() {
+        const FRAMES: usize = 4;
+        let config = RainConfig {
+            text: "IO".into(),
+            decrypt: Some(FRAMES as u8),
+            charset: vec!['0', '1'],
+            seed: Some(5),
+            ..RainConfig::default()
+        };
+        let mut state = RainState::with_config(config, 40, 12);
+        let (x0, y0) = (state.targets[0].x, state.targets[0].y);
+        let lines = state.targets[0].lines.clone();
+        let mut locked_in: Vec<Vec<Option<usize>>> =
+            lines.iter().map(|l| vec![None; l.len()]).collect();
+        for step in 0..3000 {
+            state.step(FRAME);
+            for (row, line) in lines.iter().enumerate() {
+                for (col, &ch) in line.iter().enumerate() {
+                    if ch == ' ' {
+                        continue;
+                    }
+                    if state.targets[0].locked[row][col].is_some() {
+                        locked_in[row][col].get_or_insert(step);
+                    }
+                    let Some(since) = locked_in[row][col].map(|at| step - at) else {
+                        continue;
+                    };
+                    let shown = state
+                        .grid()
+                        .get(x0 + col as u16, y0 + row as u16)
+                        .unwrap()
+                        .ch;
+                    if since < FRAMES {
+                        assert!(
+                            ['0', '1'].contains(&shown),
+                            "{row},{col} nach {since}: {shown:?}"
+                        );
+                    } else {
+                        assert_eq!(shown, ch, "{row},{col} nach {since}");
+                    }
+                }
+            }
+            if state.is_complete() {
+                return;
+            }
+        }
+        panic!("Text nie vollständig");
+    }
+}
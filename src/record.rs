@@ -0,0 +1,130 @@
+use std::io::{self, Write};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Leitet alle Ausgaben an `inner` weiter und schreibt sie zusätzlich als
+/// asciicast-v2-Aufnahme (`.cast`) mit. Jedes `flush` wird zu einem Ereignis mit
+/// Zeitstempel, bei einem Flush pro Frame also ein Ereignis pro Frame.
+///
+/// Aufgenommen werden genau die Bytes, die auch das Terminal erhält; mit
+/// `--no-color` oder reduzierter Farbtiefe enthält die Aufnahme daher ebenfalls
+/// keine bzw. quantisierte Farben.
+pub struct CastRecorder<W: Write, F: Write> {
+    inner: W,
+    cast: F,
+    pending: Vec<u8>,
+    start: Instant,
+}
+
+impl<W: Write, F: Write> CastRecorder<W, F> {
+    /// Schreibt den Kopf der Aufnahme für ein Terminal der Größe `width` x `height`.
+    pub fn new(inner: W, mut cast: F, width: u16, height: u16) -> io::Result<Self> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        writeln!(
+            cast,
+            r#"{{"version": 2, "width": {width}, "height": {height}, "timestamp": {timestamp}}}"#
+        )?;
+        Ok(Self {
+            inner,
+            cast,
+            pending: Vec::new(),
+            start: Instant::now(),
+        })
+    }
+
+    /// Schreibt ausstehende Ausgaben und schließt die Aufnahme ab.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.flush()?;
+        self.cast.flush()
+    }
+
+    fn write_event(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let data = json_escape(&String::from_utf8_lossy(&self.pending));
+        let time = self.start.elapsed().as_secs_f64();
+        writeln!(self.cast, r#"[{time:.6}, "o", "{data}"]"#)?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write, F: Write> Write for CastRecorder<W, F> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.pending.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        self.write_event()
+    }
+}
+
+impl<W: Write, F: Write> Drop for CastRecorder<W, F> {
+    fn drop(&mut self) {
+        let _ = self.write_event();
+        let _ = self.cast.flush();
+    }
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\u{7f}' => {
+                out.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_and_events_are_asciicast_lines() {
+        let (mut terminal, mut cast) = (Vec::new(), Vec::new());
+        let mut recorder = CastRecorder::new(&mut terminal, &mut cast, 80, 24).unwrap();
+        recorder.write_all(b"\x1b[2J\"hi\"\n").unwrap();
+        recorder.flush().unwrap();
+        recorder.finish().unwrap();
+
+        assert_eq!(terminal, b"\x1b[2J\"hi\"\n");
+        let cast = String::from_utf8(cast).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+        assert_eq!(lines.len(), 2, "{cast}");
+        assert!(
+            lines[0].starts_with(r#"{"version": 2, "width": 80, "height": 24, "timestamp": "#),
+            "{}",
+            lines[0]
+        );
+        assert!(lines[0].ends_with('}'));
+        assert!(
+            lines[1].starts_with('[') && lines[1].ends_with(r#", "o", "\u001b[2J\"hi\"\n"]"#),
+            "{}",
+            lines[1]
+        );
+    }
+
+    #[test]
+    fn json_escape_handles_controls_and_quotes() {
+        assert_eq!(
+            json_escape("\x1b[1m\"a\\b\"\n\r\t\x7f"),
+            r#"\u001b[1m\"a\\b\"\n\r\t\u007f"#
+        );
+        assert_eq!(json_escape("ｱ日"), "ｱ日");
+    }
+}
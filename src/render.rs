@@ -0,0 +1,349 @@
+//! Ausgabe-Backends: ein [`Renderer`] zeichnet berechnete Raster Zelle für Zelle,
+//! etwa ins Terminal ([`CrosstermRenderer`]) oder als Klartext ([`TextRenderer`]).
+
+use crate::color::ColorDepth;
+use crate::rain::{Cell, Grid};
+use crossterm::QueueableCommand;
+use crossterm::cursor::MoveTo;
+use crossterm::style::{
+    Color, PrintStyledContent, ResetColor, SetBackgroundColor, StyledContent, Stylize,
+};
+use crossterm::terminal::{Clear, ClearType};
+use std::io::{self, Write};
+
+/// Ziel, auf das ein Frame Zelle für Zelle gezeichnet wird.
+///
+/// [`Renderer::draw_grid`] ruft zwischen `begin_frame` und `end_frame` für jede zu
+/// zeichnende Zelle `draw_cell` auf; eigene Backends implementieren nur diese drei.
+///
+/// ```
+/// use matrix::{Cell, RainConfig, RainState, Renderer};
+/// use std::time::Duration;
+///
+/// /// Merkt sich jede gezeichnete Zelle
+/// #[derive(Default)]
+/// struct Recorder {
+///     frames: usize,
+///     cells: Vec<(u16, u16, char)>,
+/// }
+///
+/// impl Renderer for Recorder {
+///     fn begin_frame(&mut self, _width: u16, _height: u16) -> std::io::Result<()> {
+///         self.cells.clear();
+///         Ok(())
+///     }
+///     fn draw_cell(&mut self, x: u16, y: u16, cell: &Cell) -> std::io::Result<()> {
+///         self.cells.push((x, y, cell.ch));
+///         Ok(())
+///     }
+///     fn end_frame(&mut self) -> std::io::Result<()> {
+///         self.frames += 1;
+///         Ok(())
+///     }
+/// }
+///
+/// let config = RainConfig {
+///     text: "HI".to_string(),
+///     seed: Some(1),
+///     ..RainConfig::default()
+/// };
+/// let mut state = RainState::with_config(config, 20, 9);
+/// let mut recorder = Recorder::default();
+/// let drawn = recorder.draw_grid(state.step(Duration::from_millis(16)), None)?;
+///
+/// assert_eq!((recorder.frames, drawn), (1, 20 * 9));
+/// // Der Rahmen um den Ziel-Text steht vom ersten Frame an
+/// assert!(recorder.cells.iter().any(|&(_, _, ch)| ch == '+'));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub trait Renderer {
+    /// Beginnt einen Frame der Größe `width` x `height`.
+    fn begin_frame(&mut self, width: u16, height: u16) -> io::Result<()>;
+
+    /// Zeichnet `cell` an Rasterposition `(x, y)`.
+    fn draw_cell(&mut self, x: u16, y: u16, cell: &Cell) -> io::Result<()>;
+
+    /// Schließt den Frame ab, etwa durch Ausgeben oder Flushen.
+    fn end_frame(&mut self) -> io::Result<()>;
+
+    /// Zeichnet `grid` als einen Frame: mit `prev` nur die gegenüber diesem geänderten
+    /// Zellen, sonst alle. Liefert die Anzahl der gezeichneten Zellen.
+    fn draw_grid(&mut self, grid: &Grid, prev: Option<&Grid>) -> io::Result<usize> {
+        self.begin_frame(grid.width(), grid.height())?;
+        let mut drawn = 0;
+        match prev {
+            Some(prev) => {
+                for (x, y, cell) in grid.changes(prev) {
+                    self.draw_cell(x, y, cell)?;
+                    drawn += 1;
+                }
+            }
+            None => {
+                for (y, row) in (0..).zip(grid.rows()) {
+                    for (x, cell) in (0..).zip(row) {
+                        self.draw_cell(x, y, cell)?;
+                        drawn += 1;
+                    }
+                }
+            }
+        }
+        self.end_frame()?;
+        Ok(drawn)
+    }
+}
+
+/// Einstellungen für die Terminalausgabe.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RenderOptions {
+    /// Farben ausgeben; ohne Farbe tragen fett/gedimmt die Unterscheidung
+    pub color: bool,
+    /// Ziel-Farbtiefe, auf die RGB-Farben quantisiert werden
+    pub depth: ColorDepth,
+    /// Hintergrundfarbe hinter jeder Zelle; ohne bleibt der des Terminals
+    pub background: Option<Color>,
+    /// Füllzeichen für leere Zellen; ohne bleiben sie leer
+    pub empty_char: Option<char>,
+    /// Farbe des Füllzeichens; ohne dunkelgrau
+    pub empty_color: Option<Color>,
+}
+
+/// Gibt Zellen als ANSI-Sequenzen über crossterm aus: Cursor-Sprung und gestyltes
+/// Zeichen je Zelle, ein `flush` je Frame.
+pub struct CrosstermRenderer<W: Write> {
+    out: W,
+    options: RenderOptions,
+}
+
+impl<W: Write> CrosstermRenderer<W> {
+    pub fn new(out: W, options: RenderOptions) -> Self {
+        Self { out, options }
+    }
+
+    /// Bildschirm leeren; mit `background` in dieser Farbe, sonst im Terminal-Hintergrund.
+    pub fn clear(&mut self) -> io::Result<()> {
+        if let Some(bg) = self.options.background {
+            self.out
+                .queue(SetBackgroundColor(self.options.depth.quantize(bg)))?;
+        }
+        self.out.queue(Clear(ClearType::All))?;
+        if self.options.background.is_some() {
+            self.out.queue(ResetColor)?;
+        }
+        self.out.flush()
+    }
+
+    /// Zugriff auf die Ausgabe, etwa für Terminal-Befehle außerhalb eines Frames.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    /// Gibt die Ausgabe zurück.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Renderer for CrosstermRenderer<W> {
+    fn begin_frame(&mut self, _width: u16, _height: u16) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn draw_cell(&mut self, x: u16, y: u16, cell: &Cell) -> io::Result<()> {
+        self.out
+            .queue(MoveTo(x, y))?
+            .queue(PrintStyledContent(style_cell(cell, self.options)))?;
+        Ok(())
+    }
+
+    fn end_frame(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+fn style_cell(cell: &Cell, options: RenderOptions) -> StyledContent<char> {
+    if let Some(ch) = options.empty_char
+        && cell.is_empty()
+    {
+        let fg = options.empty_color.unwrap_or(Color::DarkGrey);
+        let filler = Cell {
+            ch,
+            fg,
+            dim: true,
+            ..Cell::EMPTY
+        };
+        return style_cell(
+            &filler,
+            RenderOptions {
+                empty_char: None,
+                ..options
+            },
+        );
+    }
+    let mut styled = if options.color {
+        cell.ch.with(options.depth.quantize(cell.fg))
+    } else {
+        cell.ch.stylize()
+    };
+    if let Some(bg) = options.background {
+        styled = styled.on(options.depth.quantize(bg));
+    }
+    if cell.bold {
+        styled = styled.bold();
+    }
+    if cell.dim && !options.color {
+        styled = styled.dim();
+    }
+    styled
+}
+
+/// Schreibt jeden Frame als Klartext ohne Farben, eine Zeile pro Rasterzeile;
+/// aufeinanderfolgende Frames trennt ein Seitenvorschub (`\f`).
+///
+/// Die Zeichen bleiben zwischen den Frames stehen, sodass auch nur die geänderten
+/// Zellen gezeichnet werden können.
+pub struct TextRenderer<W: Write> {
+    out: W,
+    width: usize,
+    chars: Vec<char>,
+    frames: usize,
+    /// Zeichen für leere Zellen
+    empty: char,
+}
+
+impl<W: Write> TextRenderer<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            width: 0,
+            chars: Vec::new(),
+            frames: 0,
+            empty: Cell::EMPTY.ch,
+        }
+    }
+
+    /// Gibt leere Zellen als `ch` statt als Leerzeichen aus.
+    pub fn with_empty_char(mut self, ch: char) -> Self {
+        self.empty = ch;
+        self
+    }
+
+    /// Bisher ausgegebene Frames.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Gibt die Ausgabe zurück.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Renderer for TextRenderer<W> {
+    fn begin_frame(&mut self, width: u16, height: u16) -> io::Result<()> {
+        let len = width as usize * height as usize;
+        if self.width != width as usize || self.chars.len() != len {
+            self.width = width as usize;
+            self.chars = vec![self.empty; len];
+        }
+        Ok(())
+    }
+
+    fn draw_cell(&mut self, x: u16, y: u16, cell: &Cell) -> io::Result<()> {
+        if let Some(ch) = self.chars.get_mut(y as usize * self.width + x as usize) {
+            *ch = if cell.is_empty() { self.empty } else { cell.ch };
+        }
+        Ok(())
+    }
+
+    fn end_frame(&mut self) -> io::Result<()> {
+        if self.frames > 0 {
+            self.out.write_all(b"\x0c")?;
+        }
+        let mut text = String::with_capacity(self.chars.len() * 4);
+        for row in self.chars.chunks(self.width.max(1)) {
+            text.extend(row);
+            text.push('\n');
+        }
+        self.out.write_all(text.as_bytes())?;
+        self.frames += 1;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Zwei Zellen nebeneinander: ein grünes `x` und eine leere
+    fn grid() -> Grid {
+        let mut grid = Grid::new(2, 1);
+        let cell = Cell {
+            ch: 'x',
+            fg: Color::Rgb { r: 0, g: 255, b: 0 },
+            ..Cell::EMPTY
+        };
+        grid.set(0, 0, cell);
+        grid
+    }
+
+    fn render(options: RenderOptions) -> String {
+        let mut renderer = CrosstermRenderer::new(Vec::new(), options);
+        renderer.draw_grid(&grid(), None).unwrap();
+        String::from_utf8(renderer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn background_is_applied_to_drawn_cells() {
+        let out = render(RenderOptions {
+            color: true,
+            background: Some(Color::Rgb { r: 1, g: 2, b: 3 }),
+            ..RenderOptions::default()
+        });
+        // Hinter dem Zeichen und hinter der leeren Zelle
+        assert_eq!(out.matches("\x1b[48;2;1;2;3m").count(), 2, "{out:?}");
+        assert!(!render(RenderOptions::default()).contains("\x1b[48;"));
+    }
+
+    #[test]
+    fn no_color_keeps_only_the_attributes() {
+        use crossterm::style::Attribute;
+        let cell = Cell {
+            ch: 'x',
+            fg: Color::Green,
+            dim: true,
+            ..Cell::EMPTY
+        };
+        let plain = style_cell(&cell, RenderOptions::default());
+        assert_eq!(plain.style().foreground_color, None);
+        assert!(plain.style().attributes.has(Attribute::Dim));
+        let options = RenderOptions {
+            color: true,
+            ..RenderOptions::default()
+        };
+        let colored = style_cell(&cell, options);
+        assert_eq!(colored.style().foreground_color, Some(Color::Green));
+    }
+
+    #[test]
+    fn empty_cells_show_the_configured_char() {
+        let out = render(RenderOptions {
+            color: true,
+            empty_char: Some('.'),
+            empty_color: Some(Color::Rgb { r: 9, g: 8, b: 7 }),
+            ..RenderOptions::default()
+        });
+        assert!(out.contains("\x1b[38;2;9;8;7m."), "{out:?}");
+        assert!(out.contains('x'));
+        // Ohne eigene Farbe dunkelgrau
+        let out = render(RenderOptions {
+            color: true,
+            empty_char: Some('.'),
+            ..RenderOptions::default()
+        });
+        assert!(out.contains("\x1b[38;5;8m."), "{out:?}");
+
+        let mut text = TextRenderer::new(Vec::new()).with_empty_char('.');
+        text.draw_grid(&grid(), None).unwrap();
+        assert_eq!(text.into_inner(), b"x.\n");
+    }
+}
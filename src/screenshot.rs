@@ -0,0 +1,151 @@
+//! Einzelbild eines Rasters als PNG, unabhängig von Terminal und Terminalschrift.
+
+use crate::color::color_to_rgb;
+use crate::font::bitmap_5x7;
+use crate::rain::Grid;
+use crossterm::style::Color;
+use std::io::{self, Write};
+
+/// Breite einer Rasterzelle im Bild in Pixeln
+pub const PNG_CELL_WIDTH: u32 = 8;
+/// Höhe einer Rasterzelle im Bild in Pixeln
+pub const PNG_CELL_HEIGHT: u32 = 16;
+
+/// Schreibt `grid` als PNG mit fester Zellgröße von [`PNG_CELL_WIDTH`] x
+/// [`PNG_CELL_HEIGHT`] Pixeln auf `background`.
+///
+/// Die Glyphen stammen aus der eingebauten 5x7-Schrift (Kleinbuchstaben als
+/// Großbuchstaben), doppelt so hoch gezeichnet; Zeichen, die sie nicht kennt
+/// (Katakana, `#` des Ziel-Texts, ...), werden zu einem vollen Block in Zellfarbe.
+pub fn write_png(grid: &Grid, background: Color, mut out: impl Write) -> io::Result<()> {
+    let width = grid.width() as u32 * PNG_CELL_WIDTH;
+    let height = grid.height() as u32 * PNG_CELL_HEIGHT;
+    let (r, g, b) = color_to_rgb(background);
+    let mut pixels = [r, g, b].repeat(width as usize * height as usize);
+
+    for (cy, row) in grid.rows().enumerate() {
+        for (cx, cell) in row.iter().enumerate() {
+            if cell.ch == ' ' || cell.is_empty() {
+                continue;
+            }
+            let rgb = color_to_rgb(cell.fg);
+            let glyph = bitmap_5x7(cell.ch);
+            for py in 0..PNG_CELL_HEIGHT {
+                for px in 0..PNG_CELL_WIDTH {
+                    // 5x7 mittig mit einem Pixel Rand, jede Glyphenzeile zwei Pixel hoch
+                    let lit = match &glyph {
+                        Some(rows) => {
+                            let (gx, gy) = (px.wrapping_sub(1), py.wrapping_sub(1) / 2);
+                            rows.get(gy as usize)
+                                .and_then(|r| r.as_bytes().get(gx as usize))
+                                .is_some_and(|b| *b == b'#')
+                        }
+                        None => true,
+                    };
+                    if lit {
+                        let x = cx as u32 * PNG_CELL_WIDTH + px;
+                        let y = cy as u32 * PNG_CELL_HEIGHT + py;
+                        let i = (y * width + x) as usize * 3;
+                        pixels[i..i + 3].copy_from_slice(&[rgb.0, rgb.1, rgb.2]);
+                    }
+                }
+            }
+        }
+    }
+
+    out.write_all(b"\x89PNG\r\n\x1a\n")?;
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 Bit je Kanal, RGB, Standard-Kompression/-Filter, kein Interlacing
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+    write_chunk(&mut out, b"IHDR", &header)?;
+    write_chunk(&mut out, b"IDAT", &zlib_stored(&scanlines(&pixels, width)))?;
+    write_chunk(&mut out, b"IEND", &[])?;
+    out.flush()
+}
+
+/// Bildzeilen mit vorangestelltem Filtertyp 0 (keiner).
+fn scanlines(pixels: &[u8], width: u32) -> Vec<u8> {
+    let stride = width as usize * 3;
+    let mut data = Vec::with_capacity(pixels.len() + pixels.len() / stride.max(1));
+    for line in pixels.chunks(stride.max(1)) {
+        data.push(0);
+        data.extend_from_slice(line);
+    }
+    data
+}
+
+/// zlib-Strom aus unkomprimierten Deflate-Blöcken; genügt für Vorschaubilder und
+/// kommt ohne Kompressionsbibliothek aus.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const BLOCK: usize = u16::MAX as usize;
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        out.push(u8::from(blocks.peek().is_none()));
+        let len = block.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn write_chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let crc = crc32(&[kind.as_slice(), data].concat());
+    out.write_all(&crc.to_be_bytes())
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65_521;
+        b = (b + a) % 65_521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn png_has_cell_sized_dimensions() {
+        let mut png = Vec::new();
+        write_png(&Grid::new(12, 5), Color::Black, &mut png).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        let be = |i: usize| u32::from_be_bytes(png[i..i + 4].try_into().unwrap());
+        assert_eq!(be(16), 12 * PNG_CELL_WIDTH);
+        assert_eq!(be(20), 5 * PNG_CELL_HEIGHT);
+    }
+
+    #[test]
+    fn checksums_match_reference_values() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+}
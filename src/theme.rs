@@ -0,0 +1,76 @@
+//! Benannte Themen, die Zeichensatz, Farbset und Rahmen als Voreinstellung bündeln.
+//!
+//! Rangfolge: Flags auf der Kommandozeile > Profil-Datei > Thema > eingebaute Standardwerte.
+
+use clap::ValueEnum;
+use std::ffi::OsString;
+
+/// Voreinstellungs-Bündel für `--theme`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Theme {
+    /// Katakana auf grünem Verlauf wie im Film
+    Classic,
+    /// Farbset 2077 mit doppeltem Rahmen
+    Cyberpunk,
+    /// Farbset thermography mit Binärziffern
+    Thermal,
+}
+
+/// Grüner Verlauf von dunkel nach hell für [`Theme::Classic`]
+const CLASSIC_GREEN: &str = "#003b00,#008f11,#00ff41";
+
+impl Theme {
+    /// Gesetzte Optionen als `(Flag-Name, Wert)`; Namen wie die langen Flags.
+    fn defaults(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Theme::Classic => &[("charset", "katakana"), ("colors", CLASSIC_GREEN)],
+            Theme::Cyberpunk => &[("colorset", "2077"), ("border", "double")],
+            Theme::Thermal => &[("colorset", "thermography"), ("charset", "binary")],
+        }
+    }
+
+    /// Übersetzt das Thema in Flags, die vor Profil und Kommandozeile stehen.
+    /// `skip` meldet Optionen, die dort schon gesetzt sind.
+    pub fn to_args(self, skip: impl Fn(&str) -> bool) -> Vec<OsString> {
+        // --colors, --colorset und --palette-file schließen sich aus; ist eins davon
+        // gesetzt, bleibt das Farbset des Themas außen vor
+        let color_set = skip("colorset") || skip("colors") || skip("palette_file") || skip("list");
+        let mut args = Vec::new();
+        for &(id, value) in self.defaults() {
+            let skipped = match id {
+                "colorset" | "colors" => color_set,
+                _ => skip(id),
+            };
+            if !skipped {
+                args.push(format!("--{id}").into());
+                args.push(value.into());
+            }
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Args;
+    use clap::Parser;
+    use matrix::{Charset, Color, hex_to_color};
+
+    #[test]
+    fn classic_gives_katakana_on_green() {
+        let argv =
+            std::iter::once(OsString::from("matrix")).chain(Theme::Classic.to_args(|_| false));
+        let args = Args::try_parse_from(argv).unwrap_or_else(|err| panic!("{err}"));
+        assert!(matches!(args.charset, Charset::Katakana));
+
+        let colors = args.colors.expect("Farbset des Themas");
+        assert!(!colors.is_empty());
+        for hex in &colors {
+            let Ok(Color::Rgb { r, g, b }) = hex_to_color(hex) else {
+                panic!("{hex} ist keine RGB-Farbe");
+            };
+            assert!(u16::from(g) > 2 * u16::from(r.max(b)), "{hex}");
+        }
+    }
+}
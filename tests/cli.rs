@@ -0,0 +1,234 @@
+//! Aufrufe des fertigen Binaries, wie sie auf der Kommandozeile stehen.
+
+use std::process::{Command, Output};
+
+/// Startet `matrix` ohne Benutzerprofil mit den gegebenen Argumenten.
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_matrix"))
+        .args(args)
+        .env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"))
+        .env_remove("HOME")
+        .output()
+        .expect("matrix startet")
+}
+
+/// Standardausgabe eines erfolgreichen Laufs.
+fn stdout_of(args: &[&str]) -> String {
+    let output = run(args);
+    assert!(
+        output.status.success(),
+        "{args:?}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("UTF-8-Ausgabe")
+}
+
+#[test]
+fn width_override_gives_forty_columns() {
+    let frame = stdout_of(&[
+        "--width", "40", "--height", "12", "--frames", "1", "--output", "-",
+    ]);
+    let rows: Vec<&str> = frame.lines().collect();
+    assert_eq!(rows.len(), 12);
+    for row in rows {
+        assert_eq!(row.chars().count(), 40, "{row:?}");
+    }
+}
+
+#[test]
+fn piped_output_falls_back_to_80x24() {
+    // Ohne TTY liefert die Größenerkennung 0 oder einen Fehler
+    let frame = stdout_of(&["--frames", "1", "--output", "-"]);
+    let rows: Vec<&str> = frame.lines().collect();
+    assert_eq!(rows.len(), 24);
+    assert!(rows.iter().all(|row| row.chars().count() == 80));
+}
+
+#[test]
+fn tiny_grids_do_not_panic() {
+    for (w, h) in [("1", "1"), ("2", "1"), ("1", "3")] {
+        stdout_of(&[
+            "--width", w, "--height", h, "--frames", "5", "--output", "-",
+        ]);
+    }
+}
+
+#[test]
+fn speed_min_above_max_is_rejected() {
+    let output = run(&[
+        "--speed-min",
+        "80",
+        "--speed-max",
+        "40",
+        "--frames",
+        "1",
+        "--output",
+        "-",
+    ]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--speed-min"), "{stderr}");
+}
+
+#[test]
+fn every_style_renders_headless() {
+    for style in ["simple", "block", "aged"] {
+        let frames = stdout_of(&[
+            "--style", style, "--width", "30", "--height", "10", "--frames", "20", "--seed", "1",
+            "--output", "-",
+        ]);
+        assert_eq!(frames.split('\x0c').count(), 20, "{style}");
+    }
+}
+
+#[test]
+fn exit_on_complete_ends_headless_run_early() {
+    let frames = stdout_of(&[
+        "--string",
+        "HI",
+        "--exit-on-complete",
+        "--width",
+        "30",
+        "--height",
+        "10",
+        "--seed",
+        "2",
+        "--frames",
+        "100000",
+        "--output",
+        "-",
+    ]);
+    let count = frames.split('\x0c').count();
+    assert!(count < 100_000, "{count}");
+    // Im letzten Frame steht der vollständige Ziel-Text
+    let last = frames.split('\x0c').next_back().unwrap();
+    assert!(last.contains("# #"), "{last}");
+}
+
+#[test]
+fn string_file_renders_like_string() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("string-file.txt");
+    std::fs::write(&path, "WAKE\nUP\n").unwrap();
+    let common = [
+        "--width", "40", "--height", "20", "--seed", "4", "--frames", "300", "--output", "-",
+    ];
+    let from_file = stdout_of(&[&["--string-file", path.to_str().unwrap()], &common[..]].concat());
+    let from_arg = stdout_of(&[&["--string", "WAKE\nUP"], &common[..]].concat());
+    assert_eq!(from_file, from_arg);
+}
+
+#[test]
+fn string_dash_reads_stdin() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let common = [
+        "--width", "30", "--height", "10", "--seed", "4", "--frames", "100", "--output", "-",
+    ];
+    let mut child = Command::new(env!("CARGO_BIN_EXE_matrix"))
+        .args(["--string", "-"])
+        .args(common)
+        .env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"))
+        .env_remove("HOME")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"HI\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let from_arg = stdout_of(&[&["--string", "HI"], &common[..]].concat());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), from_arg);
+}
+
+#[test]
+fn trail_min_above_max_is_rejected() {
+    let output = run(&[
+        "--trail-min",
+        "9",
+        "--trail-max",
+        "4",
+        "--frames",
+        "1",
+        "--output",
+        "-",
+    ]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--trail-min"), "{stderr}");
+}
+
+#[test]
+fn duration_limits_headless_frames() {
+    let common = [
+        "--width", "20", "--height", "5", "--fps", "20", "--output", "-",
+    ];
+    let count = |extra: &[&str]| {
+        stdout_of(&[extra, &common[..]].concat())
+            .split('\x0c')
+            .count()
+    };
+    assert_eq!(count(&["--duration", "0.5", "--frames", "1000"]), 10);
+    assert_eq!(count(&["--duration", "0.5"]), 10);
+    // 0 heißt endlos, es bleibt bei --frames
+    assert_eq!(count(&["--duration", "0", "--frames", "30"]), 30);
+}
+
+#[test]
+fn zero_by_zero_size_is_rejected_cleanly() {
+    let output = run(&[
+        "--width", "0", "--height", "0", "--frames", "1", "--output", "-",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--width") && !stderr.contains("panicked"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn all_zero_charset_weights_are_rejected() {
+    let output = run(&[
+        "--charset",
+        "binary",
+        "--charset-weights",
+        "0=0,1=0",
+        "--frames",
+        "1",
+        "--output",
+        "-",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--charset-weights"), "{stderr}");
+    // Ein einzelnes Zeichen auszublenden bleibt erlaubt
+    stdout_of(&[
+        "--charset",
+        "binary",
+        "--charset-weights",
+        "0=0",
+        "--frames",
+        "1",
+        "--output",
+        "-",
+    ]);
+}
+
+#[test]
+fn print_config_output_loads_with_config() {
+    let printed = stdout_of(&[
+        "--fps",
+        "25",
+        "--colors",
+        "#ff0000,#0000ff",
+        "--density",
+        "0.5",
+        "--print-config",
+    ]);
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("printed.toml");
+    std::fs::write(&path, &printed).unwrap();
+    let reloaded = stdout_of(&["--config", path.to_str().unwrap(), "--print-config"]);
+    assert_eq!(reloaded, printed);
+}